@@ -19,20 +19,39 @@
 use std::path::PathBuf;
 
 use clap::{
-    Parser,
+    ArgGroup, Parser,
     builder::{NonEmptyStringValueParser, TypedValueParser},
 };
 
-use super::one_char::OneChar;
+use super::{
+    export::Color,
+    loader::AxisValue,
+    one_char::OneChar,
+    preview::terminal::{graphics::GraphicsMode, render::RenderType},
+};
 
 fn no_newline_string_parser() -> impl TypedValueParser {
     NonEmptyStringValueParser::new().map(|s| s.replace(['\r', '\n'], ""))
 }
 
+fn parse_cell_aspect(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(ratio) if ratio.is_finite() && ratio > 0.0 => Ok(ratio),
+        _ => Err("must be a positive number".to_string()),
+    }
+}
+
 #[derive(Debug, Parser)]
-#[command(author, version, about, arg_required_else_help(true))]
+#[command(
+    author,
+    version,
+    about,
+    arg_required_else_help(true),
+    group(ArgGroup::new("export").multiple(true))
+)]
 pub struct Args {
-    /// Verbose mode, -v show all font styles, -vv adds font file and face index
+    /// Verbose mode, -v show all font styles, -vv adds font file, face index and variation,
+    /// -vvv adds glyph metrics
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
@@ -42,9 +61,18 @@ pub struct Args {
 
     /// Enable Terminal UI mode.
     /// enable this mode will disable the --preview/-p and ignore --verbose/-v option
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "export")]
     pub tui: bool,
 
+    /// Graphics protocol used to show glyph as image in Terminal UI mode, auto detected by
+    /// default
+    #[arg(long, value_enum, value_name = "PROTOCOL", default_value_t = GraphicsMode::Auto)]
+    pub graphics: GraphicsMode,
+
+    /// Height divided by width of a terminal cell, detected from terminal by default
+    #[arg(long = "cell-aspect", value_name = "RATIO", value_parser = parse_cell_aspect)]
+    pub cell_aspect: Option<f32>,
+
     /// Do not load system fonts
     #[arg(long = "no-system")]
     pub no_system: bool,
@@ -58,6 +86,81 @@ pub struct Args {
     #[arg(short = 'f', long = "filter", name = "FILTER", value_parser = no_newline_string_parser())]
     pub filter: Option<String>,
 
+    /// Also show variable fonts at this axis value, like `wght=700`.
+    /// Named instances of variable fonts are always listed.
+    /// This arg can be provided multiple times.
+    #[arg(long = "variation", name = "AXIS=VALUE", action = clap::ArgAction::Append)]
+    pub variations: Vec<AxisValue>,
+
+    /// Export glyph of every matched font face into PNG files in this directory,
+    /// instead of showing the font list
+    #[arg(long = "export-png", name = "DIR", group = "export")]
+    pub export_png: Option<PathBuf>,
+
+    /// Export glyph outline of every matched font face into SVG files in this directory
+    #[arg(
+        long = "export-svg",
+        id = "SVG_DIR",
+        value_name = "DIR",
+        group = "export"
+    )]
+    pub export_svg: Option<PathBuf>,
+
+    /// Export glyph outline of all matched font faces into one SVG file
+    #[arg(long = "export-svg-sheet", name = "FILE", group = "export")]
+    pub export_svg_sheet: Option<PathBuf>,
+
+    /// Mark on-curve and off-curve points, contour start points and directions in exported SVG
+    #[arg(long = "svg-points")]
+    pub svg_points: bool,
+
+    /// Render glyph of all matched font faces into one PNG file, with face name under each glyph
+    #[arg(long = "sheet", name = "SHEET_FILE", group = "export")]
+    pub sheet: Option<PathBuf>,
+
+    /// Print glyph of every matched font face to stdout with this render, one by one
+    #[arg(
+        long = "render",
+        value_enum,
+        name = "RENDER",
+        conflicts_with_all = ["tui", "preview", "INLINE_RENDER", "export"]
+    )]
+    pub render: Option<RenderType>,
+
+    /// Only print font faces whose name contains this string, requires --render
+    #[arg(
+        long = "font",
+        name = "NAME",
+        requires = "RENDER",
+        value_parser = no_newline_string_parser()
+    )]
+    pub font: Option<String>,
+
+    /// Show a small preview of glyph beside each family in font list, use braille(mono) render
+    /// if not specified
+    #[arg(
+        long = "inline",
+        value_enum,
+        name = "INLINE_RENDER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "mono"
+    )]
+    pub inline: Option<RenderType>,
+
+    /// Pixels per em of exported images [default: 256], lines of --render output [default: 20]
+    /// or --inline preview [default: 10]
+    #[arg(long, name = "N", value_parser = clap::value_parser!(u32).range(1..=4096))]
+    pub size: Option<u32>,
+
+    /// Glyph color of exported images, `#RRGGBB[AA]`, `black` or `white`
+    #[arg(long, value_name = "COLOR", default_value = "black")]
+    pub foreground: Color,
+
+    /// Background color of exported images, `transparent`, `#RRGGBB[AA]`, `black` or `white`
+    #[arg(long, value_name = "COLOR", default_value = "transparent")]
+    pub background: Color,
+
     /// The character
     #[arg(name = "CHAR")]
     pub char: OneChar,
@@ -0,0 +1,183 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use ab_glyph::{Font, FontRef, FontVec, PxScale, ScaleFont, point};
+use fontdb::{Family, ID, Query};
+use grid::Grid;
+
+use crate::{loader, rasterizer::Rgba};
+
+/// Text drawer for captions, each character uses the first font which contains it,
+/// a sans-serif font is tried at first.
+pub struct Caption {
+    height: f32,
+    color: Rgba,
+    fonts: Vec<ID>,
+    /// Fonts chosen by any char, parsed once and reused by all captions of a sheet
+    loaded: HashMap<ID, FontVec>,
+    chosen: HashMap<char, Option<ID>>,
+}
+
+/// A laid out character
+struct Placed {
+    font: ID,
+    c: char,
+    x: f32,
+}
+
+fn has_glyph(font: &impl Font, c: char) -> bool {
+    font.glyph_id(c).0 != 0
+}
+
+impl Caption {
+    pub fn new(height: f32, color: Rgba) -> Self {
+        let db = loader::database();
+        let preferred = db.query(&Query {
+            families: &[Family::SansSerif],
+            ..Query::default()
+        });
+
+        let fonts = preferred
+            .into_iter()
+            .chain(
+                db.faces()
+                    .map(|face| face.id)
+                    .filter(|id| Some(*id) != preferred),
+            )
+            .collect();
+
+        Self {
+            height,
+            color,
+            fonts,
+            loaded: HashMap::new(),
+            chosen: HashMap::new(),
+        }
+    }
+
+    /// Whether the font contains `c`, fonts not loaded yet are parsed in place without keeping
+    /// a copy of their data.
+    fn contains(&self, id: ID, c: char) -> bool {
+        match self.loaded.get(&id) {
+            Some(font) => has_glyph(font, c),
+            None => loader::database()
+                .with_face_data(id, |data, index| {
+                    FontRef::try_from_slice_and_index(data, index).is_ok_and(|f| has_glyph(&f, c))
+                })
+                .unwrap_or(false),
+        }
+    }
+
+    fn load(&mut self, id: ID) -> bool {
+        if self.loaded.contains_key(&id) {
+            return true;
+        }
+
+        let font = loader::database()
+            .with_face_data(id, |data, index| {
+                FontVec::try_from_vec_and_index(data.to_vec(), index).ok()
+            })
+            .flatten();
+        match font {
+            Some(font) => {
+                self.loaded.insert(id, font);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn font_for(&mut self, c: char) -> Option<ID> {
+        if let Some(id) = self.chosen.get(&c) {
+            return *id;
+        }
+
+        let id = self
+            .fonts
+            .iter()
+            .copied()
+            .find(|&id| self.contains(id, c))
+            .filter(|&id| self.load(id));
+        self.chosen.insert(c, id);
+        id
+    }
+
+    /// Place characters from left to right, stop before exceeding `max_width`.
+    fn layout(&mut self, text: &str, max_width: f32) -> (Vec<Placed>, f32) {
+        let scale = PxScale::from(self.height);
+        let mut placed = vec![];
+        let mut x = 0.0;
+        for c in text.chars() {
+            // Keep using font of previous char, to avoid mixing fonts in a word
+            let previous = placed
+                .last()
+                .map(|p: &Placed| p.font)
+                .filter(|id| has_glyph(&self.loaded[id], c));
+            let Some(font) = previous.or_else(|| self.font_for(c)) else {
+                continue;
+            };
+            let f = &self.loaded[&font];
+            let advance = f.as_scaled(scale).h_advance(f.glyph_id(c));
+            if x + advance > max_width {
+                break;
+            }
+            placed.push(Placed { font, c, x });
+            x += advance;
+        }
+        (placed, x)
+    }
+
+    /// Draw text whose baseline center is at `anchor` (x, y).
+    pub fn draw(
+        &mut self, canvas: &mut Grid<Rgba>, text: &str, anchor: (f32, f32), max_width: f32,
+    ) {
+        let (center_x, baseline) = anchor;
+        let color = self.color;
+        let (placed, width) = self.layout(text, max_width);
+        let left = center_x - width / 2.0;
+
+        let scale = PxScale::from(self.height);
+        for p in placed {
+            let font = &self.loaded[&p.font];
+            let glyph = font
+                .glyph_id(p.c)
+                .with_scale_and_position(scale, point(left + p.x, baseline));
+            let Some(outline) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|x, y, coverage| {
+                let col = bounds.min.x as i64 + i64::from(x);
+                let row = bounds.min.y as i64 + i64::from(y);
+                if col < 0 || row < 0 {
+                    return;
+                }
+                let Some(pixel) = canvas.get_mut(row as usize, col as usize) else {
+                    return;
+                };
+                let alpha = coverage * f32::from(color[3]) / 255.0;
+                *pixel = super::source_over(
+                    [color[0], color[1], color[2], (alpha * 255.0).round() as u8],
+                    *pixel,
+                );
+            });
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::rasterizer::Rgba;
+
+/// A color from command line, `transparent`, `black`, `white` or hex form like `#RGB`,
+/// `#RRGGBB` and `#RRGGBBAA`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Color(pub Rgba);
+
+impl Color {
+    pub const BLACK: Self = Self([0, 0, 0, 255]);
+    pub const TRANSPARENT: Self = Self([0, 0, 0, 0]);
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Error)]
+pub enum ParseColorError {
+    #[error("color must be `transparent`, `black`, `white` or in `#RRGGBB[AA]` format")]
+    UnknownFormat,
+    #[error("invalid hex digit in color")]
+    NotHexDigit,
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "transparent" | "none" => return Ok(Self::TRANSPARENT),
+            "black" => return Ok(Self::BLACK),
+            "white" => return Ok(Self([255, 255, 255, 255])),
+            _ => (),
+        }
+
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !hex.is_ascii() {
+            return Err(ParseColorError::UnknownFormat);
+        }
+
+        let digit = |i: usize, len: usize| {
+            let digits = &hex[i..i + len];
+            // from_str_radix accepts a leading `+`
+            if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ParseColorError::NotHexDigit);
+            }
+            u8::from_str_radix(digits, 16).map_err(|_| ParseColorError::NotHexDigit)
+        };
+
+        match hex.len() {
+            // #RGB, each digit is repeated
+            3 => Ok(Self([
+                digit(0, 1)? * 17,
+                digit(1, 1)? * 17,
+                digit(2, 1)? * 17,
+                255,
+            ])),
+            6 | 8 => Ok(Self([
+                digit(0, 2)?,
+                digit(2, 2)?,
+                digit(4, 2)?,
+                if hex.len() == 8 { digit(6, 2)? } else { 255 },
+            ])),
+            _ => Err(ParseColorError::UnknownFormat),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named() {
+        assert_eq!("Black".parse(), Ok(Color::BLACK));
+        assert_eq!("white".parse(), Ok(Color([255, 255, 255, 255])));
+        assert_eq!("transparent".parse(), Ok(Color::TRANSPARENT));
+        assert_eq!("none".parse(), Ok(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!("#1a2B3c".parse(), Ok(Color([0x1A, 0x2B, 0x3C, 255])));
+        assert_eq!("1a2b3c80".parse(), Ok(Color([0x1A, 0x2B, 0x3C, 0x80])));
+        assert_eq!("#f80".parse(), Ok(Color([0xFF, 0x88, 0x00, 255])));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!("blue".parse::<Color>(), Err(ParseColorError::UnknownFormat));
+        assert_eq!(
+            "#12345".parse::<Color>(),
+            Err(ParseColorError::UnknownFormat)
+        );
+        assert_eq!(
+            "#1234567".parse::<Color>(),
+            Err(ParseColorError::UnknownFormat)
+        );
+        assert_eq!(
+            "#12345g".parse::<Color>(),
+            Err(ParseColorError::NotHexDigit)
+        );
+        assert_eq!(
+            "#+1+2+3".parse::<Color>(),
+            Err(ParseColorError::NotHexDigit)
+        );
+        assert_eq!(
+            "#一二".parse::<Color>(),
+            Err(ParseColorError::UnknownFormat)
+        );
+    }
+}
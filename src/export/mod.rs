@@ -0,0 +1,298 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+mod caption;
+mod color;
+mod png;
+mod sheet;
+mod svg;
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use grid::Grid;
+use thiserror::Error;
+
+use self::sheet::Sheet;
+pub use self::{color::Color, png::encode as encode_png, svg::Outline};
+use crate::{
+    loader::FaceInfo,
+    rasterizer::{self, Bitmap, Options, Rgba, Size},
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Can't parse font file")]
+    InvalidFont,
+    #[error("Can't read font file")]
+    ReadFontFailed,
+    #[error(transparent)]
+    Rasterize(#[from] rasterizer::Error),
+    #[error("Target glyph is a bitmap, it has no outline")]
+    NoOutline,
+    #[error("Write file failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Encode PNG failed: {0}")]
+    Png(#[from] ::png::EncodingError),
+}
+
+/// Rasterize the glyph of a face into its em box at `ppem` pixels per em, so all faces share
+/// the same scale and baseline.
+pub fn render(face: &FaceInfo, ppem: u32, foreground: Rgba) -> Result<Bitmap, Error> {
+    let options = Options {
+        foreground,
+        em_box: true,
+        ..Options::new(Size::Em(ppem))
+    };
+    Ok(rasterizer::rasterize_face(
+        face,
+        &face.coordinates,
+        options,
+    )?)
+}
+
+/// Draw `src` over `dst`, both are not premultiplied.
+pub fn source_over(src: Rgba, dst: Rgba) -> Rgba {
+    let sa = f32::from(src[3]) / 255.0;
+    let da = f32::from(dst[3]) / 255.0 * (1.0 - sa);
+    let a = sa + da;
+    if a <= 0.0 {
+        return [0; 4];
+    }
+
+    let c = |i: usize| ((f32::from(src[i]) * sa + f32::from(dst[i]) * da) / a).round() as u8;
+    [c(0), c(1), c(2), (a * 255.0).round() as u8]
+}
+
+/// Paint bitmap on background, gray pixels use foreground color and color glyphs keep theirs.
+pub fn composite(bitmap: &Bitmap, foreground: Rgba, background: Rgba) -> Grid<Rgba> {
+    let m = bitmap.metrics();
+    let mut result = Grid::init(m.height, m.width, background);
+
+    for row in 0..m.height {
+        for col in 0..m.width {
+            let src = match bitmap.colors() {
+                Some(colors) => colors[(row, col)],
+                None => {
+                    let alpha = u16::from(bitmap.pixel(row, col)) * u16::from(foreground[3]) / 255;
+                    [foreground[0], foreground[1], foreground[2], alpha as u8]
+                }
+            };
+            result[(row, col)] = source_over(src, background);
+        }
+    }
+
+    result
+}
+
+/// Keep file name portable, and readable as much as possible.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Files in output dir, each face gets a unique file name.
+struct OutputFiles<'a> {
+    dir: &'a Path,
+    used: HashSet<String>,
+}
+
+impl<'a> OutputFiles<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Self {
+            dir,
+            used: HashSet::new(),
+        }
+    }
+
+    /// A file path in output dir which is not used by previous faces.
+    fn unique_path(&mut self, name: &str, ext: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(self.dir)?;
+
+        let stem = file_stem(name);
+        let mut candidate = stem.clone();
+        let mut i = 1;
+        while !self.used.insert(candidate.clone()) {
+            i += 1;
+            candidate = format!("{stem} ({i})");
+        }
+        Ok(self.dir.join(format!("{candidate}.{ext}")))
+    }
+}
+
+/// Export glyph of each face into a PNG file.
+pub struct PngExporter<'a> {
+    files: OutputFiles<'a>,
+    size: u32,
+    foreground: Rgba,
+    background: Rgba,
+}
+
+impl<'a> PngExporter<'a> {
+    pub fn new(dir: &'a Path, size: u32, foreground: Color, background: Color) -> Self {
+        Self {
+            files: OutputFiles::new(dir),
+            size,
+            foreground: foreground.0,
+            background: background.0,
+        }
+    }
+
+    pub fn export(&mut self, face: &FaceInfo) -> Result<PathBuf, Error> {
+        let bitmap = render(face, self.size, self.foreground)?;
+        let pixels = composite(&bitmap, self.foreground, self.background);
+
+        let path = self.files.unique_path(&face.name, "png")?;
+        png::write(&path, &pixels)?;
+
+        Ok(path)
+    }
+}
+
+/// Export glyph outline of each face into a SVG file.
+pub struct SvgExporter<'a> {
+    files: OutputFiles<'a>,
+    size: u32,
+    foreground: Rgba,
+    background: Rgba,
+    points: bool,
+}
+
+impl<'a> SvgExporter<'a> {
+    pub fn new(
+        dir: &'a Path, size: u32, foreground: Color, background: Color, points: bool,
+    ) -> Self {
+        Self {
+            files: OutputFiles::new(dir),
+            size,
+            foreground: foreground.0,
+            background: background.0,
+            points,
+        }
+    }
+
+    pub fn export(&mut self, face: &FaceInfo) -> Result<PathBuf, Error> {
+        let outline = Outline::new(face, self.points)?;
+        let document = outline.document(self.size, self.foreground, self.background);
+
+        let path = self.files.unique_path(&face.name, "svg")?;
+        std::fs::write(&path, document)?;
+
+        Ok(path)
+    }
+}
+
+/// Write outlines of all faces into one SVG file.
+pub fn write_svg_sheet(
+    path: &Path, outlines: &[Outline], foreground: Color, background: Color,
+) -> Result<(), Error> {
+    std::fs::write(path, svg::sheet(outlines, foreground.0, background.0))?;
+    Ok(())
+}
+
+/// Render glyphs of all faces into one PNG file, returns faces failed to render.
+pub fn write_png_sheet<'f>(
+    path: &Path, faces: &[&'f FaceInfo], size: u32, foreground: Color, background: Color,
+) -> Result<Vec<(&'f FaceInfo, Error)>, Error> {
+    let (pixels, failed) = Sheet::new(size, foreground.0, background.0).render(faces);
+    png::write(path, &pixels)?;
+    Ok(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_source_over() {
+        assert_eq!(source_over([255, 0, 0, 255], [0, 0, 255, 255]), [
+            255, 0, 0, 255
+        ]);
+        assert_eq!(source_over([255, 0, 0, 0], [0, 0, 255, 255]), [
+            0, 0, 255, 255
+        ]);
+        assert_eq!(source_over([255, 0, 0, 128], [0, 0, 255, 255]), [
+            128, 0, 127, 255
+        ]);
+        assert_eq!(source_over([255, 0, 0, 128], [0; 4]), [255, 0, 0, 128]);
+        assert_eq!(source_over([0; 4], [0; 4]), [0; 4]);
+    }
+
+    #[test]
+    fn composite_gray_and_color() {
+        let (foreground, background) = ([255, 255, 255, 255], [0, 0, 0, 255]);
+
+        let mut alpha = Grid::init(1, 2, [0; 4]);
+        alpha[(0, 0)] = [0, 0, 0, 255];
+        alpha[(0, 1)] = [0, 0, 0, 51];
+        let pixels = composite(&Bitmap::from_alpha(alpha, (0, 0)), foreground, background);
+        assert_eq!(pixels.into_vec(), [[255, 255, 255, 255], [51, 51, 51, 255]]);
+
+        let mut colors = Grid::init(1, 2, [0; 4]);
+        colors[(0, 0)] = [255, 0, 0, 255];
+        colors[(0, 1)] = [0, 255, 0, 255];
+        let pixels = composite(&Bitmap::from_rgba(colors, (0, 0)), foreground, background);
+        assert_eq!(pixels.into_vec(), [[255, 0, 0, 255], [0, 255, 0, 255]]);
+    }
+
+    #[test]
+    fn sanitize_file_stem() {
+        assert_eq!(
+            file_stem("Noto Sans CJK SC-Bold_1.0"),
+            "Noto Sans CJK SC-Bold_1.0"
+        );
+        assert_eq!(file_stem("思源黑体 Heavy"), "思源黑体 Heavy");
+        assert_eq!(file_stem("a/b\\c:d*e?f\"g<h>i|j"), "a_b_c_d_e_f_g_h_i_j");
+    }
+
+    #[test]
+    fn unique_file_names() {
+        let dir = std::env::temp_dir().join(format!("fontfor-test-{}", std::process::id()));
+        let mut files = OutputFiles::new(&dir);
+
+        assert_eq!(files.unique_path("A", "png").unwrap(), dir.join("A.png"));
+        assert_eq!(
+            files.unique_path("B/C", "png").unwrap(),
+            dir.join("B_C.png")
+        );
+        assert_eq!(
+            files.unique_path("A", "png").unwrap(),
+            dir.join("A (2).png")
+        );
+        assert_eq!(
+            files.unique_path("A", "svg").unwrap(),
+            dir.join("A (3).svg")
+        );
+        assert_eq!(
+            files.unique_path("B:C", "png").unwrap(),
+            dir.join("B_C (2).png")
+        );
+        assert!(dir.is_dir());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
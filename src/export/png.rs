@@ -0,0 +1,49 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use grid::Grid;
+use png::{BitDepth, ColorType, Encoder};
+
+use super::Error;
+use crate::rasterizer::Rgba;
+
+/// Write RGBA pixels into a PNG file.
+pub fn write(path: &Path, pixels: &Grid<Rgba>) -> Result<(), Error> {
+    encode(BufWriter::new(File::create(path)?), pixels)
+}
+
+/// Encode RGBA pixels as PNG into the writer.
+pub fn encode<W: Write>(writer: W, pixels: &Grid<Rgba>) -> Result<(), Error> {
+    let mut encoder = Encoder::new(writer, pixels.cols() as u32, pixels.rows() as u32);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+
+    let data: Vec<u8> = pixels.iter_rows().flatten().flatten().copied().collect();
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+
+    Ok(())
+}
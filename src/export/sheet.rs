@@ -0,0 +1,112 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use grid::Grid;
+
+use super::{Error, caption::Caption, composite, render};
+use crate::{
+    loader::FaceInfo,
+    rasterizer::{Bitmap, Rgba},
+};
+
+/// A contact sheet of glyphs of many faces, with face name under each cell.
+pub struct Sheet {
+    size: u32,
+    foreground: Rgba,
+    background: Rgba,
+}
+
+impl Sheet {
+    pub fn new(size: u32, foreground: Rgba, background: Rgba) -> Self {
+        Self {
+            size,
+            foreground,
+            background,
+        }
+    }
+
+    /// Render all faces into one image, faces can't be rendered are returned with the error.
+    pub fn render<'f>(&self, faces: &[&'f FaceInfo]) -> (Grid<Rgba>, Vec<(&'f FaceInfo, Error)>) {
+        let mut failed = vec![];
+        let glyphs: Vec<(&FaceInfo, Bitmap)> = faces
+            .iter()
+            .filter_map(|&face| match render(face, self.size, self.foreground) {
+                Ok(bitmap) => Some((face, bitmap)),
+                Err(err) => {
+                    failed.push((face, err));
+                    None
+                }
+            })
+            .collect();
+
+        let size = self.size as usize;
+        let padding = (size / 8).max(4);
+        let caption_height = (size / 8).max(12);
+
+        // All glyphs in a row share the same baseline
+        let above = glyphs
+            .iter()
+            .map(|(_, b)| b.origin().1.max(0) as usize)
+            .max();
+        let below = glyphs
+            .iter()
+            .map(|(_, b)| (b.metrics().height as i32 - b.origin().1).max(0) as usize)
+            .max();
+        let glyph_width = glyphs.iter().map(|(_, b)| b.metrics().width).max();
+
+        let (Some(above), Some(below), Some(glyph_width)) = (above, below, glyph_width) else {
+            return (Grid::init(0, 0, self.background), failed);
+        };
+
+        let cell_width = glyph_width.max(size) + padding * 2;
+        let cell_height = above + below + caption_height * 3 / 2 + padding * 2;
+
+        let columns = (glyphs.len() as f32).sqrt().ceil().max(1.0) as usize;
+        let rows = glyphs.len().div_ceil(columns);
+
+        let mut canvas = Grid::init(rows * cell_height, columns * cell_width, self.background);
+        let mut caption = Caption::new(caption_height as f32, self.foreground);
+
+        for (i, (face, bitmap)) in glyphs.iter().enumerate() {
+            let left = (i % columns) * cell_width;
+            let top = (i / columns) * cell_height;
+
+            let pixels = composite(bitmap, self.foreground, [0; 4]);
+            let x = left + (cell_width - bitmap.metrics().width) / 2;
+            let y = top + padding + above - bitmap.origin().1.max(0) as usize;
+            for row in 0..pixels.rows() {
+                for col in 0..pixels.cols() {
+                    let dst = &mut canvas[(y + row, x + col)];
+                    *dst = super::source_over(pixels[(row, col)], *dst);
+                }
+            }
+
+            caption.draw(
+                &mut canvas,
+                &face.name,
+                (
+                    (left + cell_width / 2) as f32,
+                    (top + cell_height - padding) as f32,
+                ),
+                (cell_width - padding) as f32,
+            );
+        }
+
+        (canvas, failed)
+    }
+}
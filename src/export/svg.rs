@@ -0,0 +1,360 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Write;
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+use super::Error;
+use crate::{
+    loader::{self, FaceInfo, GlyphOutline, Segment},
+    rasterizer::Rgba,
+};
+
+/// Size of em box in combined sheet, each glyph is scaled to it
+const SHEET_EM: f32 = 1000.0;
+const SHEET_CAPTION_SIZE: f32 = 100.0;
+const SHEET_GAP: f32 = 100.0;
+
+/// SVG path data in font units, y axis goes up.
+#[derive(Default)]
+struct PathData(String);
+
+impl OutlineBuilder for PathData {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let _ = write!(self.0, "M{x} {y}");
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let _ = write!(self.0, "L{x} {y}");
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let _ = write!(self.0, "Q{x1} {y1} {x} {y}");
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let _ = write!(self.0, "C{x1} {y1} {x2} {y2} {x} {y}");
+    }
+
+    fn close(&mut self) {
+        self.0.push('Z');
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `fill` and `fill-opacity` attributes of a color.
+fn fill(color: Rgba) -> String {
+    let [r, g, b, a] = color;
+    format!(
+        r##"fill="#{r:02x}{g:02x}{b:02x}" fill-opacity="{}""##,
+        f32::from(a) / 255.0
+    )
+}
+
+/// Debug marks of contours in font units: handles, on-curve and off-curve points, start points
+/// and direction arrows.
+fn point_marks(outline: &GlyphOutline) -> String {
+    let unit = f32::from(outline.units_per_em) / 100.0;
+    let (mut handles, mut points, mut starts) = (String::new(), String::new(), String::new());
+
+    for contour in &outline.contours {
+        for (from, segment) in contour.segments() {
+            match *segment {
+                Segment::Line(_) => {}
+                Segment::Quad(c, p) => {
+                    let _ = write!(
+                        handles,
+                        "M{} {}L{} {}L{} {}",
+                        from.x, from.y, c.x, c.y, p.x, p.y
+                    );
+                }
+                Segment::Cubic(c1, c2, p) => {
+                    let _ = write!(
+                        handles,
+                        "M{} {}L{} {}M{} {}L{} {}",
+                        from.x, from.y, c1.x, c1.y, c2.x, c2.y, p.x, p.y
+                    );
+                }
+            }
+            for c in segment.controls() {
+                let _ = write!(
+                    points,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="white" stroke="#e08020"/>"##,
+                    c.x,
+                    c.y,
+                    unit * 0.6
+                );
+            }
+            let p = segment.end();
+            let _ = write!(
+                points,
+                r##"<circle cx="{}" cy="{}" r="{}" fill="#2080e0"/>"##,
+                p.x,
+                p.y,
+                unit * 0.8
+            );
+        }
+
+        let s = contour.start;
+        let _ = write!(
+            starts,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none"/>"#,
+            s.x,
+            s.y,
+            unit * 1.6
+        );
+        if let Some((p, d)) = contour.direction() {
+            // Triangle points to contour direction
+            let (tip, back) = (2.0 * unit, unit);
+            let _ = write!(
+                starts,
+                r##"<path d="M{} {}L{} {}L{} {}Z" fill="#e03030" stroke="none"/>"##,
+                p.x + d.x * tip,
+                p.y + d.y * tip,
+                p.x - d.x * back - d.y * back,
+                p.y - d.y * back + d.x * back,
+                p.x - d.x * back + d.y * back,
+                p.y - d.y * back - d.x * back,
+            );
+        }
+    }
+
+    format!(
+        r##"<path d="{handles}" fill="none" stroke="#808080" stroke-width="{}"/><g stroke-width="{}">{points}</g><g stroke="#e03030" stroke-width="{}">{starts}</g>"##,
+        unit * 0.2,
+        unit * 0.2,
+        unit * 0.4,
+    )
+}
+
+/// Glyph outline of a font face, with metrics to place it in em box.
+pub struct Outline {
+    name: String,
+    path: String,
+    /// Debug marks of points and contour directions, if requested
+    marks: Option<String>,
+    units_per_em: f32,
+    /// Em box plus overflowed part of outline, (x_min, y_min, x_max, y_max) in font units
+    bounds: [f32; 4],
+}
+
+impl Outline {
+    /// Read outline of the target glyph, `points` adds debug marks of its points.
+    pub fn new(face: &FaceInfo, points: bool) -> Result<Self, Error> {
+        loader::database()
+            .with_face_data(face.id, |data, index| {
+                let mut parsed = Face::parse(data, index).map_err(|_| Error::InvalidFont)?;
+                for c in &face.coordinates {
+                    parsed.set_variation(c.tag, c.value);
+                }
+
+                let gid = GlyphId(face.gid);
+                let mut path = PathData::default();
+                let bbox = parsed.outline_glyph(gid, &mut path);
+                if bbox.is_none() && parsed.glyph_raster_image(gid, u16::MAX).is_some() {
+                    return Err(Error::NoOutline);
+                }
+
+                let advance = f32::from(parsed.glyph_hor_advance(gid).unwrap_or_default());
+                let mut bounds = [
+                    0.0,
+                    f32::from(parsed.descender()),
+                    advance,
+                    f32::from(parsed.ascender()),
+                ];
+                if let Some(b) = bbox {
+                    bounds[0] = bounds[0].min(f32::from(b.x_min));
+                    bounds[1] = bounds[1].min(f32::from(b.y_min));
+                    bounds[2] = bounds[2].max(f32::from(b.x_max));
+                    bounds[3] = bounds[3].max(f32::from(b.y_max));
+                }
+
+                Ok(Self {
+                    name: face.name.to_string(),
+                    path: path.0,
+                    marks: points.then(|| point_marks(&GlyphOutline::new(&parsed, face.gid))),
+                    units_per_em: f32::from(parsed.units_per_em()),
+                    bounds,
+                })
+            })
+            .unwrap_or(Err(Error::ReadFontFailed))
+    }
+
+    fn width(&self) -> f32 {
+        self.bounds[2] - self.bounds[0]
+    }
+
+    fn height(&self) -> f32 {
+        self.bounds[3] - self.bounds[1]
+    }
+
+    /// The path element, `transform` maps font units into the document.
+    fn element(&self, transform: &str, foreground: Rgba) -> String {
+        // Marks share the transform with path in a group
+        let path_transform = match self.marks {
+            Some(_) => String::new(),
+            None => format!(r#" transform="{transform}""#),
+        };
+        let path = format!(
+            r#"<path{path_transform} {} d="{}"/>"#,
+            fill(foreground),
+            self.path
+        );
+        match self.marks {
+            Some(ref marks) => format!(r#"<g transform="{transform}">{path}{marks}</g>"#),
+            None => path,
+        }
+    }
+
+    /// A standalone SVG document, view box is the em box in font units with baseline at y = 0.
+    pub fn document(&self, height: u32, foreground: Rgba, background: Rgba) -> String {
+        let [x_min, _, _, y_max] = self.bounds;
+        let (w, h) = (self.width(), self.height());
+        let width = (f64::from(height) * f64::from(w) / f64::from(h).max(1.0)).round();
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="{x_min} {} {w} {h}">"#,
+            -y_max,
+        );
+        let _ = write!(svg, "<title>{}</title>", escape(&self.name));
+        if background[3] > 0 {
+            let _ = write!(
+                svg,
+                r#"<rect x="{x_min}" y="{}" width="{w}" height="{h}" {}/>"#,
+                -y_max,
+                fill(background)
+            );
+        }
+        // Flip y axis so baseline stays at y = 0
+        svg.push_str(&self.element("scale(1 -1)", foreground));
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// One SVG document contains all outlines in a grid, each glyph is scaled to the same em size
+/// and glyphs in a row share the same baseline, with face name under it.
+pub fn sheet(outlines: &[Outline], foreground: Rgba, background: Rgba) -> String {
+    let scale = |o: &Outline| SHEET_EM / o.units_per_em;
+
+    let cell_width = outlines
+        .iter()
+        .map(|o| o.width() * scale(o))
+        .fold(SHEET_EM, f32::max);
+    let above = outlines
+        .iter()
+        .map(|o| o.bounds[3] * scale(o))
+        .fold(0.0, f32::max);
+    let below = outlines
+        .iter()
+        .map(|o| -o.bounds[1] * scale(o))
+        .fold(0.0, f32::max);
+    let cell_height = above + below + SHEET_CAPTION_SIZE * 1.5;
+
+    let columns = (outlines.len() as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = outlines.len().div_ceil(columns);
+
+    let width = columns as f32 * (cell_width + SHEET_GAP) + SHEET_GAP;
+    let height = rows as f32 * (cell_height + SHEET_GAP) + SHEET_GAP;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="{SHEET_CAPTION_SIZE}" text-anchor="middle">"#
+    );
+    if background[3] > 0 {
+        let _ = write!(
+            svg,
+            r#"<rect width="{width}" height="{height}" {}/>"#,
+            fill(background)
+        );
+    }
+
+    for (i, outline) in outlines.iter().enumerate() {
+        let k = scale(outline);
+        let left = SHEET_GAP + (i % columns) as f32 * (cell_width + SHEET_GAP);
+        let top = SHEET_GAP + (i / columns) as f32 * (cell_height + SHEET_GAP);
+
+        let x = left + (cell_width - outline.width() * k) / 2.0 - outline.bounds[0] * k;
+        let baseline = top + above;
+        svg.push_str(&outline.element(
+            &format!("translate({x} {baseline}) scale({k} {})", -k),
+            foreground,
+        ));
+
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{}" {}>{}</text>"#,
+            left + cell_width / 2.0,
+            top + cell_height - SHEET_CAPTION_SIZE * 0.25,
+            fill(foreground),
+            escape(&outline.name),
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(marks: Option<String>) -> Outline {
+        let mut path = PathData::default();
+        path.move_to(0.0, 0.0);
+        path.line_to(0.0, 100.0);
+        path.quad_to(50.0, 150.0, 100.0, 100.0);
+        path.line_to(100.0, 0.0);
+        path.close();
+
+        Outline {
+            name: "A & B".to_string(),
+            path: path.0,
+            marks,
+            units_per_em: 1000.0,
+            bounds: [0.0, -200.0, 500.0, 800.0],
+        }
+    }
+
+    #[test]
+    fn document() {
+        assert_eq!(
+            outline(None).document(100, [0, 0, 0, 255], [0; 4]),
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="100" viewBox="0 -800 500 1000">"#,
+                "<title>A &amp; B</title>",
+                r##"<path transform="scale(1 -1)" fill="#000000" fill-opacity="1" d="M0 0L0 100Q50 150 100 100L100 0Z"/>"##,
+                "</svg>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn document_with_marks() {
+        let document = outline(Some("<g/>".to_string())).document(100, [0, 0, 0, 255], [0; 4]);
+        assert!(document.contains(
+            r##"<g transform="scale(1 -1)"><path fill="#000000" fill-opacity="1" d="M0 0L0 100Q50 150 100 100L100 0Z"/><g/></g>"##
+        ));
+    }
+}
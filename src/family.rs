@@ -22,14 +22,14 @@ use range_set_blaze::RangeSetBlaze;
 
 use super::loader::FaceInfo;
 
-pub struct Family<'a> {
-    pub name: &'a str,
-    pub faces: Vec<&'a FaceInfo>,
+pub struct Family {
+    pub name: &'static str,
+    pub faces: Vec<FaceInfo>,
     pub default_name_width: usize,
 }
 
-impl<'a> Family<'a> {
-    pub fn new(name: &'a str) -> Self {
+impl Family {
+    pub fn new(name: &'static str) -> Self {
         Self {
             name,
             faces: vec![],
@@ -41,23 +41,23 @@ impl<'a> Family<'a> {
         self.faces.len()
     }
 
-    pub fn add_face(&mut self, face: &'a FaceInfo) {
+    pub fn add_face(&mut self, face: FaceInfo) {
         self.faces.push(face);
     }
 }
 
-pub fn group_by_family_sort_by_name(faces: &[FaceInfo]) -> Vec<Family<'_>> {
+pub fn group_by_family_sort_by_name(faces: Vec<FaceInfo>) -> Vec<Family> {
     let mut families = HashMap::new();
 
-    faces.iter().for_each(|face| {
-        let family = &face.family;
+    faces.into_iter().for_each(|face| {
+        let family = face.family;
         families
             .entry(family)
             .or_insert_with(|| Family::new(family))
             .add_face(face);
     });
 
-    let mut families: Vec<Family<'_>> = families.into_values().collect();
+    let mut families: Vec<Family> = families.into_values().collect();
 
     families.sort_by_key(|f| f.name);
 
@@ -68,45 +68,45 @@ pub fn group_by_family_sort_by_name(faces: &[FaceInfo]) -> Vec<Family<'_>> {
     families
 }
 
-pub struct FilteredFamilies<'a> {
-    data: Vec<Family<'a>>,
+pub struct FilteredFamilies {
+    data: Vec<Family>,
     names: Vec<String>,
     keyword: String,
     filtered: RangeSetBlaze<usize>,
 }
 
 #[derive(Clone)]
-pub struct FilteredFamiliesIter<'f, 'a> {
-    data: &'f [Family<'a>],
+pub struct FilteredFamiliesIter<'f> {
+    data: &'f [Family],
     range: range_set_blaze::Iter<usize, range_set_blaze::RangesIter<'f, usize>>,
 }
 
-impl<'f, 'a: 'f> FilteredFamiliesIter<'f, 'a> {
-    pub fn with_index(self) -> FilteredFamiliesWithIndexIter<'f, 'a> {
+impl<'f> FilteredFamiliesIter<'f> {
+    pub fn with_index(self) -> FilteredFamiliesWithIndexIter<'f> {
         FilteredFamiliesWithIndexIter(self)
     }
 }
 
-impl<'f, 'a: 'f> Iterator for FilteredFamiliesIter<'f, 'a> {
-    type Item = &'f Family<'a>;
+impl<'f> Iterator for FilteredFamiliesIter<'f> {
+    type Item = &'f Family;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.range.next().map(|i| &self.data[i])
     }
 }
 
-pub struct FilteredFamiliesWithIndexIter<'f, 'a>(FilteredFamiliesIter<'f, 'a>);
+pub struct FilteredFamiliesWithIndexIter<'f>(FilteredFamiliesIter<'f>);
 
-impl<'f, 'a: 'f> Iterator for FilteredFamiliesWithIndexIter<'f, 'a> {
-    type Item = (usize, &'f Family<'a>);
+impl<'f> Iterator for FilteredFamiliesWithIndexIter<'f> {
+    type Item = (usize, &'f Family);
 
     fn next(&mut self) -> Option<Self::Item> {
         self.0.range.next().map(|i| (i, &self.0.data[i]))
     }
 }
 
-impl<'a> FilteredFamilies<'a> {
-    pub fn new(families: Vec<Family<'a>>, keyword: String) -> Self {
+impl FilteredFamilies {
+    pub fn new(families: Vec<Family>, keyword: String) -> Self {
         let names = families.iter().map(|f| f.name.to_lowercase()).collect();
         let mut ret = Self {
             data: families,
@@ -134,11 +134,11 @@ impl<'a> FilteredFamilies<'a> {
         &self.filtered
     }
 
-    pub fn data(&self) -> &[Family<'a>] {
+    pub fn data(&self) -> &[Family] {
         &self.data
     }
 
-    pub fn matched(&self) -> FilteredFamiliesIter<'_, 'a> {
+    pub fn matched(&self) -> FilteredFamiliesIter<'_> {
         FilteredFamiliesIter {
             data: &self.data,
             range: self.matched_indices().iter(),
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use ttf_parser::{GlyphId, RawFace, cmap};
 
 use super::{
@@ -50,4 +52,20 @@ impl<'a> CMapTable<'a> {
             .filter_map(|sub_table| sub_table.glyph_index(c as u32))
             .next()
     }
+
+    /// All encoded chars and their glyph ids, first sub table wins like [`Self::glyph_index`].
+    pub fn mappings(&self) -> BTreeMap<char, GlyphId> {
+        let mut result = BTreeMap::new();
+        for sub_table in &self.sub_tables {
+            sub_table.codepoints(|cp| {
+                let Some(c) = char::from_u32(cp) else {
+                    return;
+                };
+                if let Some(gid) = sub_table.glyph_index(cp).filter(|gid| gid.0 != 0) {
+                    result.entry(c).or_insert(gid);
+                }
+            });
+        }
+        result
+    }
 }
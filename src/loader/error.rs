@@ -24,3 +24,6 @@ pub const BROKEN_NAME_TABLE: Error = Error::ParseTableFailed(NAME_TAG);
 pub const CMAP_TAG: Tag = Tag::from_bytes(b"cmap");
 pub const MISSING_CMAP_TABLE: Error = Error::MissingRequiredTable(CMAP_TAG);
 pub const BROKEN_CMAP_TABLE: Error = Error::ParseTableFailed(CMAP_TAG);
+
+pub const FVAR_TAG: Tag = Tag::from_bytes(b"fvar");
+pub const BROKEN_FVAR_TABLE: Error = Error::ParseTableFailed(FVAR_TAG);
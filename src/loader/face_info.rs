@@ -20,7 +20,7 @@ use std::{borrow::Cow, path::Path};
 
 use fontdb::Source;
 use ttf_parser::{
-    Language, RawFace,
+    Face, Language, RawFace,
     name::{Table as NameTable, name_id},
 };
 
@@ -28,11 +28,20 @@ use super::{
     Error, Result,
     cmap::CMapTable,
     error::{BROKEN_NAME_TABLE, MISSING_NAME_TABLE, NAME_TAG},
+    fvar::{Axis, AxisValue, FvarTable},
+    glyph_map::GlyphMap,
+    glyph_metrics::GlyphMetrics,
+    glyph_outline::GlyphOutline,
 };
 use crate::loader::database;
 
 /// FaceInfo contains basic font face info like family and name,
 /// and pre-located glyph id for target character.
+///
+/// A variable font is expanded into one FaceInfo per named instance (and one more for user
+/// specified axis values), they are all share the same font data but has different
+/// `coordinates`.
+#[derive(Clone)]
 pub struct FaceInfo {
     pub id: fontdb::ID,
 
@@ -43,6 +52,22 @@ pub struct FaceInfo {
     pub index: u32,
 
     pub gid: u16,
+
+    /// Variation coordinates of this instance, empty for non-variable font or default instance
+    pub coordinates: Vec<AxisValue>,
+}
+
+/// A virtual instance of a variable font.
+struct Instance {
+    name: InstanceName,
+    coordinates: Vec<AxisValue>,
+}
+
+enum InstanceName {
+    /// Subfamily name of a named instance in `fvar` table
+    Named(String),
+    /// Instance created by user specified axis values
+    User,
 }
 
 enum FontFaceFullName {
@@ -52,7 +77,9 @@ enum FontFaceFullName {
 }
 
 impl FaceInfo {
-    pub fn parse_if_contains(face: &'static fontdb::FaceInfo, c: char) -> Result<Option<Self>> {
+    pub fn parse_if_contains(
+        face: &'static fontdb::FaceInfo, c: char, user_values: &[AxisValue],
+    ) -> Result<Vec<Self>> {
         let path = match face.source {
             Source::File(ref path) => path,
             _ => unreachable!("we only load font file, so source must be File variant"),
@@ -60,7 +87,7 @@ impl FaceInfo {
 
         let index = face.index;
 
-        let Some((gid, name)) = database()
+        let Some((gid, name, instances)) = database()
             .with_face_data(face.id, |data, index| -> Result<_> {
                 let rf = RawFace::parse(data, index)?;
                 let Some(gid) = CMapTable::parse(rf)?.glyph_index(c) else {
@@ -68,11 +95,12 @@ impl FaceInfo {
                 };
 
                 let name = Self::parse_full_name(rf)?;
-                Ok(Some((gid.0, name)))
+                let instances = Self::parse_instances(rf, user_values)?;
+                Ok(Some((gid.0, name, instances)))
             })
             .expect("we only load font from database so it must not None")?
         else {
-            return Ok(None);
+            return Ok(vec![]);
         };
 
         let family = face
@@ -111,14 +139,172 @@ impl FaceInfo {
             }
         };
 
-        Ok(Some(FaceInfo {
+        let base = |name: Cow<'static, str>, coordinates: Vec<AxisValue>| FaceInfo {
             id: face.id,
             family,
             name,
             path,
             index: face.index,
             gid,
-        }))
+            coordinates,
+        };
+
+        if instances.is_empty() {
+            return Ok(vec![base(name, vec![])]);
+        }
+
+        Ok(instances
+            .into_iter()
+            .map(|instance| {
+                let instance_name = match instance.name {
+                    InstanceName::Named(sub) => format!("{family} {sub}"),
+                    InstanceName::User => {
+                        let values = instance
+                            .coordinates
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>();
+                        format!("{name} ({})", values.join(", "))
+                    }
+                };
+                base(instance_name.into(), instance.coordinates)
+            })
+            .collect())
+    }
+
+    /// Read variation axes of this face, empty if it's not a variable font.
+    pub fn variation_axes(&self) -> Result<Vec<Axis>> {
+        database()
+            .with_face_data(self.id, |data, index| -> Result<_> {
+                let rf = RawFace::parse(data, index)?;
+                let Some(fvar) = FvarTable::parse(rf)? else {
+                    return Ok(vec![]);
+                };
+
+                let name_table = rf.table(NAME_TAG).and_then(NameTable::parse);
+
+                Ok(fvar
+                    .axes
+                    .iter()
+                    .map(|axis| Axis {
+                        tag: axis.tag,
+                        name: name_table
+                            .as_ref()
+                            .and_then(|table| Self::find_name(table, axis.name_id)),
+                        min_value: axis.min_value,
+                        def_value: axis.def_value,
+                        max_value: axis.max_value,
+                    })
+                    .collect())
+            })
+            .expect("we only load font from database so it must not None")
+    }
+
+    /// Read metrics of the target glyph at variation `coordinates`.
+    pub fn glyph_metrics(&self, coordinates: &[AxisValue]) -> Result<GlyphMetrics> {
+        database()
+            .with_face_data(self.id, |data, index| -> Result<_> {
+                let mut face = Face::parse(data, index)?;
+                for c in coordinates {
+                    face.set_variation(c.tag, c.value);
+                }
+                Ok(GlyphMetrics::new(&face, self.gid))
+            })
+            .expect("we only load font from database so it must not None")
+    }
+
+    /// Read all chars and glyphs of this face.
+    pub fn glyph_map(&self) -> Result<GlyphMap> {
+        database()
+            .with_face_data(self.id, |data, index| -> Result<_> {
+                GlyphMap::new(&Face::parse(data, index)?)
+            })
+            .expect("we only load font from database so it must not None")
+    }
+
+    /// Read contours of the target glyph at variation `coordinates`.
+    pub fn glyph_outline(&self, coordinates: &[AxisValue]) -> Result<GlyphOutline> {
+        database()
+            .with_face_data(self.id, |data, index| -> Result<_> {
+                let mut face = Face::parse(data, index)?;
+                for c in coordinates {
+                    face.set_variation(c.tag, c.value);
+                }
+                Ok(GlyphOutline::new(&face, self.gid))
+            })
+            .expect("we only load font from database so it must not None")
+    }
+
+    /// Ascender and descender in ems.
+    pub fn vertical_ems(&self) -> Result<(f32, f32)> {
+        database()
+            .with_face_data(self.id, |data, index| -> Result<_> {
+                let face = Face::parse(data, index)?;
+                let upem = f32::from(face.units_per_em());
+                Ok((
+                    f32::from(face.ascender()) / upem,
+                    f32::from(face.descender()) / upem,
+                ))
+            })
+            .expect("we only load font from database so it must not None")
+    }
+
+    fn parse_instances(rf: RawFace<'_>, user_values: &[AxisValue]) -> Result<Vec<Instance>> {
+        let Some(fvar) = FvarTable::parse(rf)? else {
+            return Ok(vec![]);
+        };
+
+        let name_data = rf.table(NAME_TAG).ok_or(MISSING_NAME_TABLE)?;
+        let name_table = NameTable::parse(name_data).ok_or(BROKEN_NAME_TABLE)?;
+
+        let mut instances = fvar
+            .named_instances()?
+            .into_iter()
+            .filter_map(|instance| {
+                let Some(sub) = Self::find_name(&name_table, instance.subfamily_name_id) else {
+                    log::warn!(
+                        "Named instance do not have a subfamily name(id = {}), skip it",
+                        instance.subfamily_name_id
+                    );
+                    return None;
+                };
+
+                Some(Instance {
+                    name: InstanceName::Named(sub),
+                    coordinates: instance.coordinates,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let user = fvar.apply_user_values(user_values);
+        if !user.is_empty() {
+            instances.push(Instance {
+                name: InstanceName::User,
+                coordinates: user,
+            });
+        }
+
+        Ok(instances)
+    }
+
+    fn find_name(name_table: &NameTable<'_>, id: u16) -> Option<String> {
+        let mut fallback = None;
+
+        for name in name_table.names {
+            if name.name_id != id || !name.is_unicode() {
+                continue;
+            }
+
+            if name.language() == Language::English_UnitedStates {
+                if let Some(s) = name.to_string() {
+                    return Some(s);
+                }
+            } else if fallback.is_none() {
+                fallback = name.to_string();
+            }
+        }
+
+        fallback
     }
 
     fn parse_full_name(rf: RawFace<'_>) -> Result<FontFaceFullName> {
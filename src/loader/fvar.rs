@@ -0,0 +1,284 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+use ttf_parser::{RawFace, Tag, fvar::VariationAxis};
+
+use super::{
+    Result,
+    error::{BROKEN_FVAR_TABLE, FVAR_TAG},
+};
+
+/// A user space coordinate on one variation axis, like `wght=700`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AxisValue {
+    pub tag: Tag,
+    pub value: f32,
+}
+
+impl Display for AxisValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.tag, self.value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Error)]
+pub enum ParseAxisValueError {
+    #[error("axis value must be in `TAG=VALUE` format")]
+    MissingEqualSign,
+    #[error("axis tag must be 1 to 4 ASCII characters")]
+    InvalidTag,
+    #[error("axis value must be a number")]
+    InvalidValue,
+}
+
+impl FromStr for AxisValue {
+    type Err = ParseAxisValueError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (tag, value) = s
+            .split_once('=')
+            .ok_or(ParseAxisValueError::MissingEqualSign)?;
+
+        if tag.is_empty() || tag.len() > 4 || !tag.is_ascii() {
+            return Err(ParseAxisValueError::InvalidTag);
+        }
+
+        // Tags shorter than 4 chars are padded with spaces, see OpenType spec
+        let mut bytes = [b' '; 4];
+        bytes[..tag.len()].copy_from_slice(tag.as_bytes());
+
+        let value = value
+            .trim()
+            .parse()
+            .map_err(|_| ParseAxisValueError::InvalidValue)?;
+
+        Ok(Self {
+            tag: Tag::from_bytes(&bytes),
+            value,
+        })
+    }
+}
+
+/// A variation axis with its name resolved from `name` table.
+#[derive(Debug, Clone)]
+pub struct Axis {
+    pub tag: Tag,
+    pub name: Option<String>,
+    pub min_value: f32,
+    pub def_value: f32,
+    pub max_value: f32,
+}
+
+/// A named instance record in `fvar` table.
+pub struct NamedInstance {
+    pub subfamily_name_id: u16,
+    pub coordinates: Vec<AxisValue>,
+}
+
+pub struct FvarTable<'a> {
+    pub axes: Vec<VariationAxis>,
+    data: &'a [u8],
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_fixed(data: &[u8], offset: usize) -> Option<f32> {
+    let raw = i32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?);
+    Some(raw as f32 / 65536.0)
+}
+
+impl<'a> FvarTable<'a> {
+    /// Parse `fvar` table of the font face, returns `None` if it is not a variable font.
+    pub fn parse(rf: RawFace<'a>) -> Result<Option<Self>> {
+        let Some(data) = rf.table(FVAR_TAG) else {
+            return Ok(None);
+        };
+
+        let table = ttf_parser::fvar::Table::parse(data).ok_or(BROKEN_FVAR_TABLE)?;
+
+        Ok(Some(Self {
+            axes: table.axes.into_iter().collect(),
+            data,
+        }))
+    }
+
+    /// Read all named instances, ttf-parser only provide axes so we parse them by hand.
+    pub fn named_instances(&self) -> Result<Vec<NamedInstance>> {
+        let data = self.data;
+
+        // majorVersion, minorVersion, axesArrayOffset, reserved,
+        // axisCount, axisSize, instanceCount, instanceSize
+        let axes_offset = read_u16(data, 4).ok_or(BROKEN_FVAR_TABLE)? as usize;
+        let axis_count = read_u16(data, 8).ok_or(BROKEN_FVAR_TABLE)? as usize;
+        let axis_size = read_u16(data, 10).ok_or(BROKEN_FVAR_TABLE)? as usize;
+        let instance_count = read_u16(data, 12).ok_or(BROKEN_FVAR_TABLE)? as usize;
+        let instance_size = read_u16(data, 14).ok_or(BROKEN_FVAR_TABLE)? as usize;
+
+        if instance_size < 4 + axis_count * 4 {
+            return Err(BROKEN_FVAR_TABLE);
+        }
+
+        let start = axes_offset + axis_count * axis_size;
+
+        (0..instance_count)
+            .map(|i| {
+                let offset = start + i * instance_size;
+                let subfamily_name_id = read_u16(data, offset).ok_or(BROKEN_FVAR_TABLE)?;
+                let coordinates = self
+                    .axes
+                    .iter()
+                    .enumerate()
+                    .map(|(j, axis)| {
+                        read_fixed(data, offset + 4 + j * 4)
+                            .map(|value| AxisValue {
+                                tag: axis.tag,
+                                value,
+                            })
+                            .ok_or(BROKEN_FVAR_TABLE)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(NamedInstance {
+                    subfamily_name_id,
+                    coordinates,
+                })
+            })
+            .collect()
+    }
+
+    /// Keep only values whose axis exists in this font, and clamp them into axis range.
+    pub fn apply_user_values(&self, values: &[AxisValue]) -> Vec<AxisValue> {
+        values
+            .iter()
+            .filter_map(|v| {
+                let axis = self.axes.iter().find(|axis| axis.tag == v.tag)?;
+                Some(AxisValue {
+                    tag: v.tag,
+                    value: v.value.clamp(axis.min_value, axis.max_value),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(value: f32) -> [u8; 4] {
+        ((value * 65536.0) as i32).to_be_bytes()
+    }
+
+    /// `fvar` table with `wght` and `wdth` axes and two instances, each instance record has a
+    /// postScriptNameID if `ps_name` is set.
+    fn fvar(ps_name: bool) -> Vec<u8> {
+        let instance_size: u16 = if ps_name { 14 } else { 12 };
+        let mut data = vec![];
+        for v in [1, 0, 16, 2, 2, 20, 2, instance_size] {
+            data.extend(u16::to_be_bytes(v));
+        }
+        for (tag, min, def, max) in [
+            (b"wght", 100.0, 400.0, 900.0),
+            (b"wdth", 75.0, 100.0, 125.0),
+        ] {
+            data.extend(tag);
+            data.extend([fixed(min), fixed(def), fixed(max)].concat());
+            data.extend([0, 0, 1, 0]);
+        }
+        for (name_id, wght, wdth) in [(258u16, 700.0, 100.0), (259, 300.0, 87.5)] {
+            data.extend(name_id.to_be_bytes());
+            data.extend([0, 0]);
+            data.extend([fixed(wght), fixed(wdth)].concat());
+            if ps_name {
+                data.extend(u16::to_be_bytes(0xFFFF));
+            }
+        }
+        data
+    }
+
+    fn table(data: &[u8]) -> FvarTable<'_> {
+        let table = ttf_parser::fvar::Table::parse(data).unwrap();
+        FvarTable {
+            axes: table.axes.into_iter().collect(),
+            data,
+        }
+    }
+
+    fn instances(data: &[u8]) -> Vec<(u16, Vec<String>)> {
+        table(data)
+            .named_instances()
+            .unwrap()
+            .into_iter()
+            .map(|i| {
+                let coordinates = i.coordinates.iter().map(ToString::to_string).collect();
+                (i.subfamily_name_id, coordinates)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn instances_with_and_without_ps_name() {
+        let expected = vec![
+            (258, vec!["wght=700".to_string(), "wdth=100".to_string()]),
+            (259, vec!["wght=300".to_string(), "wdth=87.5".to_string()]),
+        ];
+        assert_eq!(instances(&fvar(false)), expected);
+        assert_eq!(instances(&fvar(true)), expected);
+    }
+
+    #[test]
+    fn broken_instances() {
+        // Instance size smaller than coordinates
+        let mut data = fvar(false);
+        data[14..16].copy_from_slice(&8u16.to_be_bytes());
+        assert!(table(&data).named_instances().is_err());
+
+        // Last instance is truncated
+        let data = fvar(true);
+        assert!(table(&data[..data.len() - 6]).named_instances().is_err());
+    }
+
+    #[test]
+    fn parse_axis_value() {
+        let value: AxisValue = "wght=700".parse().unwrap();
+        assert_eq!(value.tag, Tag::from_bytes(b"wght"));
+        assert_eq!(value.value, 700.0);
+
+        // Short tag is padded with spaces
+        let value: AxisValue = "wd= 87.5".parse().unwrap();
+        assert_eq!(value.tag, Tag::from_bytes(b"wd  "));
+        assert_eq!(value.value, 87.5);
+    }
+
+    #[test]
+    fn parse_bad_axis_value() {
+        let parse = |s: &str| s.parse::<AxisValue>().unwrap_err();
+        assert_eq!(parse("wght"), ParseAxisValueError::MissingEqualSign);
+        assert_eq!(parse("=700"), ParseAxisValueError::InvalidTag);
+        assert_eq!(parse("weight=700"), ParseAxisValueError::InvalidTag);
+        assert_eq!(parse("wé=700"), ParseAxisValueError::InvalidTag);
+        assert_eq!(parse("wght="), ParseAxisValueError::InvalidValue);
+        assert_eq!(parse("wght=bold"), ParseAxisValueError::InvalidValue);
+    }
+}
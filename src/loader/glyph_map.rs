@@ -0,0 +1,71 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use ttf_parser::{Face, GlyphId};
+
+use super::{Result, cmap::CMapTable};
+
+/// All glyphs of a face, encoded or not.
+pub struct GlyphMap {
+    /// Encoded chars and their glyph ids, sorted by code point
+    pub chars: Vec<(char, u16)>,
+    /// Glyph names from `post` or `CFF` table, indexed by glyph id
+    pub names: Vec<Option<String>>,
+    /// The first char mapped to each encoded glyph
+    encoded: HashMap<u16, char>,
+}
+
+impl GlyphMap {
+    pub fn new(face: &Face<'_>) -> Result<Self> {
+        let chars: Vec<_> = CMapTable::parse(*face.raw_face())?
+            .mappings()
+            .into_iter()
+            .map(|(c, gid)| (c, gid.0))
+            .collect();
+
+        let mut encoded = HashMap::new();
+        for &(c, gid) in &chars {
+            encoded.entry(gid).or_insert(c);
+        }
+
+        let names = (0..face.number_of_glyphs())
+            .map(|gid| face.glyph_name(GlyphId(gid)).map(ToString::to_string))
+            .collect();
+
+        Ok(Self {
+            chars,
+            names,
+            encoded,
+        })
+    }
+
+    /// Number of glyphs in `maxp` table.
+    pub fn num_glyphs(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn char_of(&self, gid: u16) -> Option<char> {
+        self.encoded.get(&gid).copied()
+    }
+
+    pub fn name_of(&self, gid: u16) -> Option<&str> {
+        self.names.get(usize::from(gid))?.as_deref()
+    }
+}
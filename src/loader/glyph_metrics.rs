@@ -0,0 +1,141 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Display;
+
+use ttf_parser::{Face, GlyphId, Rect};
+
+/// Where the glyph data comes from, a glyph may have more than one of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GlyphFormat {
+    Glyf,
+    Cff,
+    Cff2,
+    Colr,
+    Svg,
+    Sbix,
+    Cbdt,
+    Ebdt,
+}
+
+impl Display for GlyphFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Glyf => "glyf",
+            Self::Cff => "CFF",
+            Self::Cff2 => "CFF2",
+            Self::Colr => "COLR",
+            Self::Svg => "SVG",
+            Self::Sbix => "sbix",
+            Self::Cbdt => "CBDT",
+            Self::Ebdt => "EBDT",
+        })
+    }
+}
+
+/// Metrics of a glyph in font units.
+pub struct GlyphMetrics {
+    pub gid: u16,
+    /// Glyph name from `post` or `CFF` table
+    pub name: Option<String>,
+    pub units_per_em: u16,
+    pub advance_width: Option<u16>,
+    /// Only exists when font has `vmtx` table
+    pub advance_height: Option<u16>,
+    pub left_side_bearing: Option<i16>,
+    pub top_side_bearing: Option<i16>,
+    /// Bounding box of outline, `None` for empty or bitmap only glyph
+    pub bbox: Option<Rect>,
+    pub formats: Vec<GlyphFormat>,
+}
+
+impl GlyphMetrics {
+    pub fn new(face: &Face<'_>, gid: u16) -> Self {
+        let glyph_id = GlyphId(gid);
+        let tables = face.tables();
+
+        let mut formats = vec![];
+        if tables.glyf.is_some() {
+            formats.push(GlyphFormat::Glyf);
+        } else if tables.cff.is_some() {
+            formats.push(GlyphFormat::Cff);
+        } else if tables.cff2.is_some() {
+            formats.push(GlyphFormat::Cff2);
+        }
+        if face.is_color_glyph(glyph_id) {
+            formats.push(GlyphFormat::Colr);
+        }
+        if face.glyph_svg_image(glyph_id).is_some() {
+            formats.push(GlyphFormat::Svg);
+        }
+        if face.glyph_raster_image(glyph_id, u16::MAX).is_some() {
+            // Same order as ttf-parser looks up raster images
+            if tables.sbix.is_some() {
+                formats.push(GlyphFormat::Sbix);
+            } else if tables.cbdt.is_some() {
+                formats.push(GlyphFormat::Cbdt);
+            } else if tables.ebdt.is_some() {
+                formats.push(GlyphFormat::Ebdt);
+            }
+        }
+
+        Self {
+            gid,
+            name: face.glyph_name(glyph_id).map(ToString::to_string),
+            units_per_em: face.units_per_em(),
+            advance_width: face.glyph_hor_advance(glyph_id),
+            advance_height: face.glyph_ver_advance(glyph_id),
+            left_side_bearing: face.glyph_hor_side_bearing(glyph_id),
+            top_side_bearing: face.glyph_ver_side_bearing(glyph_id),
+            bbox: face.glyph_bounding_box(glyph_id),
+            formats,
+        }
+    }
+
+    pub fn format_names(&self) -> String {
+        let names: Vec<_> = self.formats.iter().map(ToString::to_string).collect();
+        names.join("+")
+    }
+}
+
+/// Display text of a metric value, `-` if missing.
+pub fn or_dash<T: Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+impl Display for GlyphMetrics {
+    /// One line summary, `-` for missing values.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gid={} name={} adv={},{} lsb={} tsb={} bbox={} upem={} format={}",
+            self.gid,
+            self.name.as_deref().unwrap_or("-"),
+            or_dash(self.advance_width),
+            or_dash(self.advance_height),
+            or_dash(self.left_side_bearing),
+            or_dash(self.top_side_bearing),
+            or_dash(
+                self.bbox
+                    .map(|b| format!("{},{},{},{}", b.x_min, b.y_min, b.x_max, b.y_max))
+            ),
+            self.units_per_em,
+            self.format_names(),
+        )
+    }
+}
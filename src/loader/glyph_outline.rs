@@ -0,0 +1,244 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+/// Curves are split into this many lines when flattened
+const FLATTEN_STEPS: usize = 8;
+
+/// A point in font units, y axis goes up.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+}
+
+/// One segment of a contour, it starts at the end point of previous segment.
+#[derive(Debug, Copy, Clone)]
+pub enum Segment {
+    Line(Point),
+    /// Off-curve control point and on-curve end point
+    Quad(Point, Point),
+    /// Two off-curve control points and on-curve end point
+    Cubic(Point, Point, Point),
+}
+
+impl Segment {
+    /// The on-curve end point.
+    pub fn end(&self) -> Point {
+        match *self {
+            Self::Line(p) | Self::Quad(_, p) | Self::Cubic(_, _, p) => p,
+        }
+    }
+
+    /// Off-curve control points.
+    pub fn controls(&self) -> Vec<Point> {
+        match *self {
+            Self::Line(_) => vec![],
+            Self::Quad(c, _) => vec![c],
+            Self::Cubic(c1, c2, _) => vec![c1, c2],
+        }
+    }
+
+    /// Point at `t` in `0..=1` of this segment which starts at `from`.
+    pub fn at(&self, from: Point, t: f32) -> Point {
+        match *self {
+            Self::Line(p) => from.lerp(p, t),
+            Self::Quad(c, p) => from.lerp(c, t).lerp(c.lerp(p, t), t),
+            Self::Cubic(c1, c2, p) => {
+                let (a, b, c) = (from.lerp(c1, t), c1.lerp(c2, t), c2.lerp(p, t));
+                a.lerp(b, t).lerp(b.lerp(c, t), t)
+            }
+        }
+    }
+
+    /// Points of polyline approximation, without `from`.
+    pub fn flatten(&self, from: Point) -> Vec<Point> {
+        match self {
+            Self::Line(p) => vec![*p],
+            _ => (1..=FLATTEN_STEPS)
+                .map(|i| self.at(from, i as f32 / FLATTEN_STEPS as f32))
+                .collect(),
+        }
+    }
+}
+
+/// A closed contour.
+#[derive(Debug, Clone)]
+pub struct Contour {
+    pub start: Point,
+    pub segments: Vec<Segment>,
+}
+
+impl Contour {
+    /// Segments with their start points.
+    pub fn segments(&self) -> impl Iterator<Item = (Point, &Segment)> {
+        let starts = std::iter::once(self.start).chain(self.segments.iter().map(Segment::end));
+        starts.zip(self.segments.iter())
+    }
+
+    /// Middle point of the first non-empty segment and the unit direction of contour there.
+    pub fn direction(&self) -> Option<(Point, Point)> {
+        self.segments().find_map(|(from, segment)| {
+            let (a, b) = (segment.at(from, 0.45), segment.at(from, 0.55));
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            let len = dx.hypot(dy);
+            (len > f32::EPSILON).then(|| (segment.at(from, 0.5), Point::new(dx / len, dy / len)))
+        })
+    }
+}
+
+#[derive(Default)]
+struct ContourBuilder {
+    contours: Vec<Contour>,
+}
+
+impl ContourBuilder {
+    fn push(&mut self, segment: Segment) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.segments.push(segment);
+        }
+    }
+}
+
+impl OutlineBuilder for ContourBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(Contour {
+            start: Point::new(x, y),
+            segments: vec![],
+        });
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(Segment::Line(Point::new(x, y)));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push(Segment::Quad(Point::new(x1, y1), Point::new(x, y)));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.push(Segment::Cubic(
+            Point::new(x1, y1),
+            Point::new(x2, y2),
+            Point::new(x, y),
+        ));
+    }
+
+    fn close(&mut self) {
+        // Make the closing line explicit, so every contour ends at its start point
+        if let Some(contour) = self.contours.last_mut() {
+            let end = contour.segments.last().map(Segment::end);
+            if end.is_some_and(|end| end != contour.start) {
+                contour.segments.push(Segment::Line(contour.start));
+            }
+        }
+    }
+}
+
+/// Contours of a glyph with metrics of its em box, all in font units.
+#[derive(Debug, Clone)]
+pub struct GlyphOutline {
+    /// Empty for space and bitmap only glyph
+    pub contours: Vec<Contour>,
+    pub units_per_em: u16,
+    pub ascender: i16,
+    pub descender: i16,
+    pub cap_height: Option<i16>,
+    pub x_height: Option<i16>,
+    pub advance: u16,
+}
+
+impl GlyphOutline {
+    pub fn new(face: &Face<'_>, gid: u16) -> Self {
+        let glyph_id = GlyphId(gid);
+        let mut builder = ContourBuilder::default();
+        face.outline_glyph(glyph_id, &mut builder);
+
+        Self {
+            contours: builder.contours,
+            units_per_em: face.units_per_em(),
+            ascender: face.ascender(),
+            descender: face.descender(),
+            cap_height: face.capital_height(),
+            x_height: face.x_height(),
+            advance: face.glyph_hor_advance(glyph_id).unwrap_or_default(),
+        }
+    }
+
+    /// Number of on-curve and off-curve points.
+    pub fn point_count(&self) -> (usize, usize) {
+        self.contours
+            .iter()
+            .flat_map(|c| c.segments.iter())
+            .fold((0, 0), |(on, off), s| (on + 1, off + s.controls().len()))
+    }
+
+    /// Bounds of all points including off-curve ones, (x_min, y_min, x_max, y_max).
+    pub fn point_bounds(&self) -> Option<[f32; 4]> {
+        self.contours
+            .iter()
+            .flat_map(|c| {
+                std::iter::once(c.start).chain(c.segments.iter().flat_map(|s| {
+                    let mut points = s.controls();
+                    points.push(s.end());
+                    points
+                }))
+            })
+            .fold(None, |bounds, p| {
+                let [x_min, y_min, x_max, y_max] = bounds.unwrap_or([p.x, p.y, p.x, p.y]);
+                Some([
+                    x_min.min(p.x),
+                    y_min.min(p.y),
+                    x_max.max(p.x),
+                    y_max.max(p.y),
+                ])
+            })
+    }
+
+    /// Em box plus overflowed points, (x_min, y_min, x_max, y_max).
+    pub fn em_bounds(&self) -> [f32; 4] {
+        let em = [
+            0.0,
+            f32::from(self.descender),
+            f32::from(self.advance),
+            f32::from(self.ascender),
+        ];
+        self.point_bounds().map_or(em, |b| {
+            [
+                em[0].min(b[0]),
+                em[1].min(b[1]),
+                em[2].max(b[2]),
+                em[3].max(b[3]),
+            ]
+        })
+    }
+}
@@ -18,13 +18,24 @@
 
 mod face_info;
 mod cmap;
+mod fvar;
+mod glyph_map;
+mod glyph_metrics;
+mod glyph_outline;
 mod error;
 
 use std::{path::Path, sync::OnceLock};
 
 use fontdb::Database;
 
-pub use self::{error::Error, face_info::FaceInfo};
+pub use self::{
+    error::Error,
+    face_info::FaceInfo,
+    fvar::{Axis, AxisValue},
+    glyph_map::GlyphMap,
+    glyph_metrics::{GlyphMetrics, or_dash},
+    glyph_outline::{GlyphOutline, Point, Segment},
+};
 pub type Result<T> = std::result::Result<T, Error>;
 
 static DATABASE: OnceLock<Database> = OnceLock::new();
@@ -51,18 +62,21 @@ pub fn database() -> &'static Database {
     DATABASE.get().expect("use after init")
 }
 
-pub fn query(c: char) -> Vec<FaceInfo> {
+/// Find all font faces which contains the character.
+///
+/// Variable fonts are expanded into named instances, `user_values` adds one more instance to
+/// them if any of the axes exists in the font.
+pub fn query(c: char, user_values: &[AxisValue]) -> Vec<FaceInfo> {
     database()
         .faces()
-        .filter_map(|info| {
-            let face = FaceInfo::parse_if_contains(info, c);
+        .flat_map(|info| {
+            let faces = FaceInfo::parse_if_contains(info, c, user_values);
 
-            if let Err(ref err) = face {
+            if let Err(ref err) = faces {
                 log::warn!("Fail to get font face name of {:?}: {}", info.source, err)
             }
 
-            face.transpose()
+            faces.unwrap_or_default()
         })
-        .filter_map(|f| f.ok())
         .collect()
 }
@@ -19,20 +19,32 @@
 #![deny(rust_2018_idioms, unsafe_code)]
 
 mod args;
+mod export;
 mod loader;
 mod family;
 mod rasterizer;
 mod one_char;
 mod preview;
+mod unicode_block;
 
 use std::{
     io::{Read, Write},
     net::SocketAddr,
+    path::PathBuf,
 };
 
 use args::Args;
+use export::{Outline, PngExporter, SvgExporter};
 use family::{Family, FilteredFamilies};
-use preview::{browser::ServerBuilder as PreviewServerBuilder, terminal::ui::UI};
+use loader::FaceInfo;
+use preview::{
+    browser::ServerBuilder as PreviewServerBuilder,
+    terminal::{
+        cell::{CellSize, DEFAULT_ASPECT as DEFAULT_CELL_ASPECT},
+        print::{self, PrintStyle},
+        ui::UI,
+    },
+};
 
 fn init(arg: &Args) {
     #[cfg(feature = "log-output")]
@@ -54,8 +66,8 @@ fn main() {
 
     init(&argument);
 
-    let font_set = loader::query(argument.char.0);
-    let families = family::group_by_family_sort_by_name(&font_set);
+    let font_set = loader::query(argument.char.0, &argument.variations);
+    let families = family::group_by_family_sort_by_name(font_set);
     if families.is_empty() {
         eprintln!(
             "No font support this character {}.",
@@ -66,8 +78,87 @@ fn main() {
 
     let filtered = FilteredFamilies::new(families, argument.filter.unwrap_or_default());
 
+    // Only detect cell size when needed, it may query the terminal
+    let cell_aspect = || {
+        argument
+            .cell_aspect
+            .or_else(|| CellSize::detect_once().map(CellSize::aspect))
+            .unwrap_or(DEFAULT_CELL_ASPECT)
+    };
+
+    if let Some(rt) = argument.render {
+        let style = PrintStyle {
+            rt,
+            rows: argument.size.unwrap_or(20),
+            aspect: cell_aspect(),
+        };
+        print_glyphs(&filtered, style, argument.font.as_deref());
+        return;
+    }
+
+    let size = argument.size.unwrap_or(256);
+    let (foreground, background) = (argument.foreground, argument.background);
+    let mut exported = false;
+
+    if let Some(ref dir) = argument.export_png {
+        let mut exporter = PngExporter::new(dir, size, foreground, background);
+        export_each(&filtered, |face| exporter.export(face));
+        exported = true;
+    }
+
+    if let Some(ref dir) = argument.export_svg {
+        let mut exporter = SvgExporter::new(dir, size, foreground, background, argument.svg_points);
+        export_each(&filtered, |face| exporter.export(face));
+        exported = true;
+    }
+
+    if let Some(ref path) = argument.export_svg_sheet {
+        let outlines: Vec<_> = filtered
+            .matched()
+            .flat_map(|family| family.faces.iter())
+            .filter_map(|face| {
+                Outline::new(face, argument.svg_points)
+                    .inspect_err(|err| eprintln!("{}\t{}", face.name, err))
+                    .ok()
+            })
+            .collect();
+        match export::write_svg_sheet(path, &outlines, foreground, background) {
+            Ok(()) => println!("{}", path.to_string_lossy()),
+            Err(err) => eprintln!("{err}"),
+        }
+        exported = true;
+    }
+
+    if let Some(ref path) = argument.sheet {
+        let faces: Vec<_> = filtered
+            .matched()
+            .flat_map(|family| family.faces.iter())
+            .collect();
+        match export::write_png_sheet(path, &faces, size, foreground, background) {
+            Ok(failed) => {
+                for (face, err) in failed {
+                    eprintln!("{}\t{}", face.name, err);
+                }
+                println!("{}", path.to_string_lossy());
+            }
+            Err(err) => eprintln!("{err}"),
+        }
+        exported = true;
+    }
+
+    if exported {
+        return;
+    }
+
     if argument.tui {
-        let ui = UI::new(filtered).expect("family length checked before, must not empty");
+        let ui = UI::new(
+            argument.char,
+            filtered,
+            argument.variations,
+            argument.graphics,
+            argument.cell_aspect,
+        )
+        .expect("family length checked before, must not empty");
         if let Err(err) = ui.show() {
             eprintln!("{err:?}");
         };
@@ -82,7 +173,12 @@ fn main() {
             "Font(s) support the character {}:",
             argument.char.description()
         );
-        show_font_list(filtered.matched(), argument.verbose);
+        let inline = argument.inline.map(|rt| PrintStyle {
+            rt,
+            rows: argument.size.unwrap_or(10),
+            aspect: cell_aspect(),
+        });
+        show_font_list(filtered.matched(), argument.verbose, inline);
 
         if let Some(builder) = builder {
             builder
@@ -92,6 +188,50 @@ fn main() {
     }
 }
 
+fn print_glyphs(filtered: &FilteredFamilies, style: PrintStyle, font: Option<&str>) {
+    let font = font.map(str::to_lowercase);
+    let faces: Vec<_> = filtered
+        .matched()
+        .flat_map(|family| family.faces.iter())
+        .filter(|face| {
+            font.as_ref()
+                .is_none_or(|name| face.name.to_lowercase().contains(name))
+        })
+        .collect();
+
+    if faces.is_empty() {
+        eprintln!("No font face matches {}", font.unwrap_or_default());
+        return;
+    }
+
+    // Headers are only needed when there are many faces
+    let header = faces.len() > 1;
+    for (i, face) in faces.into_iter().enumerate() {
+        if header {
+            if i > 0 {
+                println!();
+            }
+            println!("{}:", face.name);
+        }
+        match print::render_lines(face, style) {
+            Ok(lines) => lines.iter().for_each(|line| println!("{line}")),
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
+
+fn export_each<F>(filtered: &FilteredFamilies, mut export: F)
+where
+    F: FnMut(&FaceInfo) -> Result<PathBuf, export::Error>,
+{
+    for face in filtered.matched().flat_map(|family| family.faces.iter()) {
+        match export(face) {
+            Ok(path) => println!("{}\t{}", face.name, path.to_string_lossy()),
+            Err(err) => eprintln!("{}\t{}", face.name, err),
+        }
+    }
+}
+
 fn show_preview_addr_and_wait(addr: SocketAddr) {
     println!("{}", "-".repeat(40));
     println!("Please visit http://{addr}/ in your browser for preview");
@@ -106,9 +246,23 @@ fn show_preview_addr_and_wait(addr: SocketAddr) {
         .expect("read from stdout should not fail");
 }
 
-fn show_font_list<'f, 'a: 'f, F>(families: F, verbose: u8)
+/// Lines of a small glyph preview, or the error message.
+fn inline_preview(face: &FaceInfo, style: PrintStyle) -> Vec<String> {
+    print::render_lines(face, style).unwrap_or_else(|err| vec![err.to_string()])
+}
+
+fn family_summary(family: &Family, family_name_length: usize) -> String {
+    format!(
+        "{:<family_name_length$} with {} style{}",
+        family.name,
+        family.styles_count(),
+        if family.styles_count() > 1 { "s" } else { "" },
+    )
+}
+
+fn show_font_list<'f, F>(families: F, verbose: u8, inline: Option<PrintStyle>)
 where
-    F: Iterator<Item = &'f Family<'a>> + Clone,
+    F: Iterator<Item = &'f Family> + Clone,
 {
     let max_len = if verbose > 0 {
         0
@@ -120,24 +274,47 @@ where
             .unwrap_or_default()
     };
 
+    // Inline preview is shown at right side of family summary, so they need the same width
+    let summary_width = families
+        .clone()
+        .map(|f| family_summary(f, max_len).chars().count())
+        .max()
+        .unwrap_or_default();
+
     families.for_each(|family| {
         if verbose > 0 {
             println!("{}", family.name);
+            if let Some(inline) = inline {
+                for line in inline_preview(&family.faces[0], inline) {
+                    println!("\t{line}");
+                }
+            }
             for face in family.faces.iter() {
                 print!("\t{}", face.name);
                 if verbose > 1 {
-                    print!("\t{}:{}", face.path.to_string_lossy(), face.index)
+                    print!("\t{}:{}", face.path.to_string_lossy(), face.index);
+                    if !face.coordinates.is_empty() {
+                        let values: Vec<_> =
+                            face.coordinates.iter().map(ToString::to_string).collect();
+                        print!("\t{}", values.join(","));
+                    }
+                }
+                if verbose > 2 {
+                    match face.glyph_metrics(&face.coordinates) {
+                        Ok(metrics) => print!("\t{metrics}"),
+                        Err(err) => print!("\t{err}"),
+                    }
                 }
                 println!()
             }
+        } else if let Some(inline) = inline {
+            let summary = family_summary(family, max_len);
+            for (i, line) in inline_preview(&family.faces[0], inline).iter().enumerate() {
+                let left = if i == 0 { summary.as_str() } else { "" };
+                println!("{left:<summary_width$}  {line}");
+            }
         } else {
-            println!(
-                "{:<family_name_length$} with {} style{}",
-                family.name,
-                family.styles_count(),
-                if family.styles_count() > 1 { "s" } else { "" },
-                family_name_length = max_len,
-            );
+            println!("{}", family_summary(family, max_len));
         }
     });
 }
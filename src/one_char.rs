@@ -18,8 +18,11 @@
 
 use std::{convert::TryFrom, str::FromStr};
 
+use finl_unicode::categories::{CharacterCategories, MinorCategory};
 use thiserror::Error;
 
+use super::unicode_block::Block;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct OneChar(pub char);
 
@@ -43,6 +46,123 @@ impl OneChar {
     }
 }
 
+/// Characters from `cp` in direction of `step`, invalid scalar values are skipped.
+fn scalar_values(cp: u32, step: i64) -> impl Iterator<Item = OneChar> {
+    std::iter::successors(Some(i64::from(cp)), move |x| Some(x + step))
+        .skip(1)
+        .take_while(|x| (0..=i64::from(u32::from(char::MAX))).contains(x))
+        .filter_map(|x| char::from_u32(x as u32).map(OneChar))
+}
+
+impl OneChar {
+    pub fn next_scalar(self) -> Option<Self> {
+        scalar_values(self.0.into(), 1).next()
+    }
+
+    pub fn prev_scalar(self) -> Option<Self> {
+        scalar_values(self.0.into(), -1).next()
+    }
+
+    pub fn is_assigned(self) -> bool {
+        self.0.get_minor_category() != MinorCategory::Cn
+    }
+
+    pub fn next_assigned(self) -> Option<Self> {
+        scalar_values(self.0.into(), 1).find(|c| c.is_assigned())
+    }
+
+    pub fn prev_assigned(self) -> Option<Self> {
+        scalar_values(self.0.into(), -1).find(|c| c.is_assigned())
+    }
+
+    /// Whether the char can be printed to terminal as is, control chars may break the screen.
+    pub fn is_printable(self) -> bool {
+        !matches!(
+            self.0.get_minor_category(),
+            MinorCategory::Cc
+                | MinorCategory::Cf
+                | MinorCategory::Zl
+                | MinorCategory::Zp
+                | MinorCategory::Cn
+        )
+    }
+
+    /// Short label like `"a" U+0061`, the char itself is omitted if not printable.
+    pub fn label(self) -> String {
+        let cp = u32::from(self.0);
+        if self.is_printable() {
+            format!("\"{}\" U+{cp:04X}", self.0)
+        } else {
+            format!("U+{cp:04X}")
+        }
+    }
+
+    /// Label with block and general category, like `"a" U+0061 Basic Latin, Lowercase Letter`.
+    pub fn summary(self) -> String {
+        match self.block() {
+            Some(block) => format!("{} {}, {}", self.label(), block.name, self.category()),
+            None => format!("{} {}", self.label(), self.category()),
+        }
+    }
+
+    pub fn block(self) -> Option<Block> {
+        Block::of(self.0.into())
+    }
+
+    /// First character of next block, surrogate blocks are skipped.
+    pub fn next_block(self) -> Option<Self> {
+        std::iter::successors(Block::after(self.0.into()), |b| Block::after(b.first))
+            .find_map(|b| Self::from_scalar_value(b.first).ok())
+    }
+
+    /// First character of current block, or previous block if it's already the first one.
+    /// Surrogate blocks are skipped.
+    pub fn prev_block(self) -> Option<Self> {
+        let cp = u32::from(self.0);
+        let block = self
+            .block()
+            .filter(|b| b.first < cp)
+            .or_else(|| Block::before(cp));
+        std::iter::successors(block, |b| Block::before(b.first))
+            .find_map(|b| Self::from_scalar_value(b.first).ok())
+    }
+
+    /// Name of Unicode general category.
+    pub fn category(self) -> &'static str {
+        match self.0.get_minor_category() {
+            MinorCategory::Lu => "Uppercase Letter",
+            MinorCategory::Ll => "Lowercase Letter",
+            MinorCategory::Lt => "Titlecase Letter",
+            MinorCategory::Lm => "Modifier Letter",
+            MinorCategory::Lo => "Other Letter",
+            MinorCategory::Mn => "Nonspacing Mark",
+            MinorCategory::Mc => "Spacing Mark",
+            MinorCategory::Me => "Enclosing Mark",
+            MinorCategory::Nd => "Decimal Number",
+            MinorCategory::Nl => "Letter Number",
+            MinorCategory::No => "Other Number",
+            MinorCategory::Pc => "Connector Punctuation",
+            MinorCategory::Pd => "Dash Punctuation",
+            MinorCategory::Ps => "Open Punctuation",
+            MinorCategory::Pe => "Close Punctuation",
+            MinorCategory::Pi => "Initial Punctuation",
+            MinorCategory::Pf => "Final Punctuation",
+            MinorCategory::Po => "Other Punctuation",
+            MinorCategory::Sm => "Math Symbol",
+            MinorCategory::Sk => "Modifier Symbol",
+            MinorCategory::Sc => "Currency Symbol",
+            MinorCategory::So => "Other Symbol",
+            MinorCategory::Zs => "Space Separator",
+            MinorCategory::Zl => "Line Separator",
+            MinorCategory::Zp => "Paragraph Separator",
+            MinorCategory::Cc => "Control",
+            MinorCategory::Cf => "Format",
+            MinorCategory::Co => "Private Use",
+            MinorCategory::Cn => "Unassigned",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Error)]
 pub enum ParseError {
     #[error("you don't input anything")]
@@ -143,3 +263,35 @@ impl FromStr for OneChar {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(cp: u32) -> OneChar {
+        OneChar::from_scalar_value(cp).unwrap()
+    }
+
+    #[test]
+    fn next_scalar_skips_surrogates() {
+        assert_eq!(c(0xD7FF).next_scalar(), Some(c(0xE000)));
+        assert_eq!(c(0xE000).prev_scalar(), Some(c(0xD7FF)));
+        assert_eq!(OneChar(char::MAX).next_scalar(), None);
+        assert_eq!(OneChar('\0').prev_scalar(), None);
+    }
+
+    #[test]
+    fn prev_block_at_first_code_point() {
+        assert_eq!(c(0x0081).prev_block(), Some(c(0x0080)));
+        assert_eq!(c(0x0080).prev_block(), Some(c(0x0000)));
+        assert_eq!(c(0x0000).prev_block(), None);
+    }
+
+    #[test]
+    fn block_jumps_skip_surrogates() {
+        // Hangul Jamo Extended-B, then surrogates, then Private Use Area
+        assert_eq!(c(0xD7B0).next_block(), Some(c(0xE000)));
+        assert_eq!(c(0xE000).prev_block(), Some(c(0xD7B0)));
+        assert_eq!(c(0x10FFFF).next_block(), None);
+    }
+}
@@ -26,8 +26,8 @@ pub struct Builder<'a> {
     families: Vec<&'a str>,
 }
 
-impl<'a, 'iter> FromIterator<&'iter Family<'a>> for Builder<'a> {
-    fn from_iter<T: IntoIterator<Item = &'iter Family<'a>>>(iter: T) -> Self {
+impl<'a, 'iter> FromIterator<&'iter Family> for Builder<'a> {
+    fn from_iter<T: IntoIterator<Item = &'iter Family>>(iter: T) -> Self {
         let mut builder = Self::default();
         iter.into_iter().for_each(|f| {
             builder.add_family(f);
@@ -37,7 +37,7 @@ impl<'a, 'iter> FromIterator<&'iter Family<'a>> for Builder<'a> {
 }
 
 impl<'a> Builder<'a> {
-    pub fn add_family(&mut self, family: &Family<'a>) -> &mut Self {
+    pub fn add_family(&mut self, family: &Family) -> &mut Self {
         self.families.push(family.name);
         self
     }
@@ -0,0 +1,82 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ratatui::crossterm::terminal;
+
+use super::query;
+
+/// Cell height divided by width of most terminal fonts
+pub const DEFAULT_ASPECT: f32 = 2.0;
+
+/// Pixel size of a terminal cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CellSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CellSize {
+    /// Common cell size when terminal does not tell, or a cell of given aspect ratio.
+    pub fn fallback(aspect: Option<f32>) -> Self {
+        let width = 8;
+        let height = (width as f32 * aspect.unwrap_or(DEFAULT_ASPECT)).round() as u32;
+        Self { width, height }
+    }
+
+    /// Cell size from window pixel size of `TIOCGWINSZ`, which is cheap and follows font zoom.
+    pub fn from_window() -> Option<Self> {
+        let size = terminal::window_size().ok()?;
+        if size.width == 0 || size.height == 0 || size.columns == 0 || size.rows == 0 {
+            return None;
+        }
+        Some(Self {
+            width: u32::from(size.width / size.columns),
+            height: u32::from(size.height / size.rows),
+        })
+    }
+
+    /// Cell size from window, or `CSI 16 t` query whose answer is `CSI 6 ; height ; width t`.
+    ///
+    /// Terminal must be in raw mode and no one else is reading input.
+    pub fn detect() -> Option<Self> {
+        Self::from_window().or_else(|| {
+            let response = query::query("\x1b[16t")?;
+            let (_, answer) = response.split_once("\x1b[6;")?;
+            let (answer, _) = answer.split_once('t')?;
+            let (height, width) = answer.split_once(';')?;
+            let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+            (width > 0 && height > 0).then_some(Self { width, height })
+        })
+    }
+
+    /// Same as [`Self::detect`], but enables raw mode when needed, for non TUI mode.
+    pub fn detect_once() -> Option<Self> {
+        if terminal::is_raw_mode_enabled().unwrap_or(false) {
+            return Self::detect();
+        }
+        terminal::enable_raw_mode().ok()?;
+        let size = Self::detect();
+        terminal::disable_raw_mode().ok()?;
+        size
+    }
+
+    /// Height divided by width.
+    pub fn aspect(self) -> f32 {
+        self.height as f32 / self.width as f32
+    }
+}
@@ -0,0 +1,241 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{collections::HashMap, fmt::Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use clap::ValueEnum;
+use grid::Grid;
+
+use super::query;
+use crate::{export, rasterizer::Rgba};
+
+/// Kitty graphics protocol payload is sent in chunks of this size
+const KITTY_CHUNK: usize = 4096;
+
+/// Which graphics protocol is used to show glyph image in TUI.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, ValueEnum)]
+pub enum GraphicsMode {
+    /// Detect from environment and terminal queries
+    #[default]
+    Auto,
+    /// Do not show images, use char renders only
+    None,
+    Kitty,
+    Sixel,
+    #[value(name = "iterm2")]
+    ITerm2,
+}
+
+impl GraphicsMode {
+    /// Protocol to use, `Auto` queries terminal so it must be called in raw mode.
+    pub fn protocol(self) -> Option<Protocol> {
+        match self {
+            Self::Auto => Protocol::detect(),
+            Self::None => None,
+            Self::Kitty => Some(Protocol::Kitty),
+            Self::Sixel => Some(Protocol::Sixel),
+            Self::ITerm2 => Some(Protocol::ITerm2),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    ITerm2,
+}
+
+impl Protocol {
+    fn detect() -> Option<Self> {
+        let env = |key| std::env::var(key).unwrap_or_default();
+
+        if env("TERM") == "xterm-kitty"
+            || env("TERM_PROGRAM") == "ghostty"
+            || std::env::var_os("KITTY_WINDOW_ID").is_some()
+        {
+            return Some(Self::Kitty);
+        }
+
+        if matches!(env("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm")
+            || env("LC_TERMINAL") == "iTerm2"
+        {
+            return Some(Self::ITerm2);
+        }
+
+        // Kitty answers a query action with OK, Sixel support is the 4th attribute of DA1
+        let response = query::query("\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\")?;
+        if response.contains("\x1b_Gi=31;OK") {
+            Some(Self::Kitty)
+        } else if query::device_attributes(&response).contains(&"4") {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Escape sequence which removes all shown images, `None` if images are only removed by
+    /// redrawing cells over them.
+    pub fn clear(self) -> Option<&'static str> {
+        match self {
+            Self::Kitty => Some("\x1b_Ga=d,d=A,q=2\x1b\\"),
+            Self::Sixel | Self::ITerm2 => None,
+        }
+    }
+
+    /// Escape sequence which shows the image at cursor position, without moving the cursor.
+    pub fn encode(self, pixels: &Grid<Rgba>) -> String {
+        match self {
+            Self::Kitty => kitty(&png(pixels)),
+            Self::Sixel => sixel(pixels),
+            Self::ITerm2 => iterm2(&png(pixels), pixels),
+        }
+    }
+}
+
+fn png(pixels: &Grid<Rgba>) -> Vec<u8> {
+    let mut data = Vec::new();
+    export::encode_png(&mut data, pixels).expect("encode PNG into memory should not fail");
+    data
+}
+
+fn kitty(png: &[u8]) -> String {
+    let payload = BASE64.encode(png);
+    let chunks: Vec<_> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+
+    let mut result = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).expect("base64 is ascii");
+        if i == 0 {
+            // PNG format, transmit and display, quiet, do not move cursor
+            write!(result, "\x1b_Gf=100,a=T,q=2,C=1,m={more};{chunk}\x1b\\")
+        } else {
+            write!(result, "\x1b_Gm={more};{chunk}\x1b\\")
+        }
+        .expect("write to string should not fail");
+    }
+    result
+}
+
+fn iterm2(png: &[u8], pixels: &Grid<Rgba>) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={}px;height={}px;preserveAspectRatio=0;\
+         doNotMoveCursor=1:{}\x07",
+        png.len(),
+        pixels.cols(),
+        pixels.rows(),
+        BASE64.encode(png)
+    )
+}
+
+/// Palette color of a pixel, composited over black, `None` for transparent pixels.
+///
+/// Grays get 32 levels, other colors use a 6x6x6 cube, so there are at most 248 colors.
+fn sixel_color([r, g, b, a]: Rgba) -> Option<[u8; 3]> {
+    if a < 16 {
+        return None;
+    }
+    let over_black = |v: u8| (u16::from(v) * u16::from(a) / 255) as u8;
+    let (r, g, b) = (over_black(r), over_black(g), over_black(b));
+
+    let quantize = |v: u8, levels: u16| {
+        let step = 255 / (levels - 1);
+        ((u16::from(v) + step / 2) / step * step) as u8
+    };
+    Some(if r == g && g == b {
+        [quantize(r, 32); 3]
+    } else {
+        [quantize(r, 6), quantize(g, 6), quantize(b, 6)]
+    })
+}
+
+/// Append `count` repeats of a sixel char, with run-length encoding.
+fn sixel_run(out: &mut String, c: char, count: usize) {
+    if count > 3 {
+        write!(out, "!{count}{c}").expect("write to string should not fail");
+    } else {
+        out.extend(std::iter::repeat_n(c, count));
+    }
+}
+
+fn sixel(pixels: &Grid<Rgba>) -> String {
+    let (width, height) = (pixels.cols(), pixels.rows());
+
+    let colors: Grid<Option<[u8; 3]>> =
+        Grid::from_vec(pixels.iter().copied().map(sixel_color).collect(), width);
+
+    let colors_ref = &colors;
+    let mut palette: HashMap<[u8; 3], usize> = HashMap::new();
+    // Transparent background, image size in pixels
+    let mut out = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+    for color in colors.iter().flatten() {
+        let next = palette.len();
+        palette.entry(*color).or_insert_with(|| {
+            let [r, g, b] = color.map(|v| u32::from(v) * 100 / 255);
+            write!(out, "#{next};2;{r};{g};{b}").expect("write to string should not fail");
+            next
+        });
+    }
+
+    // Each sixel char covers a column of 6 pixels
+    for band in (0..height).step_by(6) {
+        let mut band_colors: Vec<_> = (band..(band + 6).min(height))
+            .flat_map(|row| (0..width).filter_map(move |col| colors_ref[(row, col)]))
+            .map(|color| palette[&color])
+            .collect();
+        band_colors.sort_unstable();
+        band_colors.dedup();
+
+        for index in band_colors {
+            write!(out, "#{index}").expect("write to string should not fail");
+            let mut run: Option<(char, usize)> = None;
+            for col in 0..width {
+                let bits = (0..6)
+                    .filter(|dy| {
+                        colors
+                            .get(band + dy, col)
+                            .copied()
+                            .flatten()
+                            .is_some_and(|c| palette[&c] == index)
+                    })
+                    .fold(0u8, |bits, dy| bits | 1 << dy);
+                let c = char::from(63 + bits);
+                run = match run {
+                    Some((prev, count)) if prev == c => Some((c, count + 1)),
+                    Some((prev, count)) => {
+                        sixel_run(&mut out, prev, count);
+                        Some((c, 1))
+                    }
+                    None => Some((c, 1)),
+                };
+            }
+            // Trailing blank sixels can be omitted
+            if let Some((c, count)) = run.filter(|(c, _)| *c != '?') {
+                sixel_run(&mut out, c, count);
+            }
+            // Back to band start for next color
+            out.push('$');
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
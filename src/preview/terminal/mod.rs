@@ -16,5 +16,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod cell;
+pub mod graphics;
+pub mod print;
+mod query;
 pub mod render;
 pub mod ui;
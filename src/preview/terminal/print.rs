@@ -0,0 +1,144 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use grid::Grid;
+
+use super::render::{
+    AsciiRender, AsciiRenders, BlockCell, BlockRender, ColorDepth, EdgeRender, EdgeRenders,
+    MonoRender, MoonRender, Render, RenderType, Rgb,
+};
+use crate::{
+    loader::FaceInfo,
+    rasterizer::{self, Options, Size},
+};
+
+/// Braille dot bits of each pixel in a 2x4 cell, indexed by [row][col]
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Pack every 2x4 pixels into one braille char.
+pub fn braille(bitmap: &Grid<bool>) -> Vec<String> {
+    (0..bitmap.rows().div_ceil(4))
+        .map(|row| {
+            (0..bitmap.cols().div_ceil(2))
+                .map(|col| {
+                    let mut bits = 0;
+                    for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                        for (dx, dot) in dots.iter().enumerate() {
+                            if bitmap.get(row * 4 + dy, col * 2 + dx) == Some(&true) {
+                                bits |= dot;
+                            }
+                        }
+                    }
+                    // Blank braille char is not whitespace, use space instead so it can be trimmed
+                    if bits == 0 {
+                        ' '
+                    } else {
+                        char::from_u32(0x2800 + bits).expect("braille chars are valid")
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// SGR parameters to set foreground (`base` = 38) or background (`base` = 48) color.
+fn sgr_color(base: u8, color: Rgb, depth: ColorDepth) -> String {
+    match depth {
+        ColorDepth::TrueColor => format!("{base};2;{};{};{}", color[0], color[1], color[2]),
+        ColorDepth::Ansi256 => format!("{base};5;{}", ColorDepth::ansi256(color)),
+    }
+}
+
+/// Colored cells as ANSI escaped text, blank cells are plain spaces so they can be trimmed.
+fn colored_lines(cells: &Grid<BlockCell>, depth: ColorDepth) -> Vec<String> {
+    cells
+        .iter_rows()
+        .map(|row| {
+            row.map(|cell| {
+                if cell.is_blank() {
+                    " ".to_string()
+                } else {
+                    format!(
+                        "\x1b[{};{}m{}\x1b[0m",
+                        sgr_color(38, cell.fg, depth),
+                        sgr_color(48, cell.bg, depth),
+                        cell.c
+                    )
+                }
+            })
+            .collect()
+        })
+        .collect()
+}
+
+fn lines(chars: &Grid<char>) -> Vec<String> {
+    chars.iter_rows().map(String::from_iter).collect()
+}
+
+/// How glyphs are printed.
+#[derive(Debug, Copy, Clone)]
+pub struct PrintStyle {
+    pub rt: RenderType,
+    /// Output lines
+    pub rows: u32,
+    /// Terminal cell height divided by width
+    pub aspect: f32,
+}
+
+/// Render glyph of the face into lines of text.
+pub fn render_lines(face: &FaceInfo, style: PrintStyle) -> Result<Vec<String>, &'static str> {
+    let PrintStyle { rt, rows, aspect } = style;
+
+    match rt {
+        RenderType::Image => return Err("Image render is only available in TUI mode"),
+        RenderType::Outline => return Err("Outline render is only available in TUI mode"),
+        _ => {}
+    }
+
+    let (_, cell_rows) = rt.cell_size();
+    let options = Options {
+        hscale: rt.hscale(aspect),
+        ..Options::new(Size::Height(rows * cell_rows))
+    };
+    let bitmap =
+        rasterizer::rasterize_face(face, &face.coordinates, options).map_err(|e| e.message())?;
+
+    let lines = match rt {
+        RenderType::AsciiLevel10 => lines(&AsciiRender::new(AsciiRenders::Level10).render(&bitmap)),
+        RenderType::AsciiLevel70 => lines(&AsciiRender::new(AsciiRenders::Level70).render(&bitmap)),
+        RenderType::Moon => lines(&MoonRender::new().render(&bitmap)),
+        RenderType::Edge => lines(&EdgeRender::new(EdgeRenders::BoxDrawing).render(&bitmap)),
+        RenderType::EdgeAscii => lines(&EdgeRender::new(EdgeRenders::Ascii).render(&bitmap)),
+        RenderType::Mono => braille(&MonoRender::default().render(&bitmap)),
+        RenderType::Image | RenderType::Outline => unreachable!("checked above"),
+        RenderType::HalfBlock | RenderType::Quadrant | RenderType::Sextant => {
+            let render = BlockRender::new(rt.block().expect("block render type"));
+            colored_lines(&render.render(&bitmap), ColorDepth::detect())
+        }
+    };
+
+    let mut lines: Vec<_> = lines
+        .into_iter()
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    Ok(lines)
+}
@@ -0,0 +1,92 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+/// How long to wait for terminal to answer a query
+const TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Primary device attributes request, almost all terminals answer it
+const DA1: &str = "\x1b[c";
+
+/// Whether the response ends with the answer of DA1 request, i.e. `CSI ? Ps ; ... c`.
+fn ends_with_da1(response: &[u8]) -> bool {
+    let Some(start) = response.windows(3).rposition(|w| w == b"\x1b[?") else {
+        return false;
+    };
+    match response[start + 3..].split_last() {
+        Some((b'c', params)) => params.iter().all(|b| b.is_ascii_digit() || *b == b';'),
+        _ => false,
+    }
+}
+
+/// Send the request to terminal and read all the responses.
+///
+/// A DA1 request is sent after it, so reading stops at the DA1 answer, even if terminal
+/// ignores the request. Terminal must be in raw mode and no one else is reading input.
+#[cfg(unix)]
+pub fn query(request: &str) -> Option<String> {
+    use std::{
+        fs::File,
+        io::{Read, Write},
+        time::Instant,
+    };
+
+    use rustix::event::{PollFd, PollFlags, Timespec, poll};
+
+    let mut tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    write!(tty, "{request}{DA1}").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 256];
+    while !ends_with_da1(&response) {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        let timeout = Timespec::try_from(remaining).ok()?;
+        let ready = poll(&mut [PollFd::new(&tty, PollFlags::IN)], Some(&timeout)).ok()?;
+        if ready == 0 {
+            return None;
+        }
+        let n = tty.read(&mut buf).ok()?;
+        if n == 0 {
+            return None;
+        }
+        response.extend_from_slice(&buf[..n]);
+    }
+
+    Some(String::from_utf8_lossy(&response).into_owned())
+}
+
+#[cfg(not(unix))]
+pub fn query(_request: &str) -> Option<String> {
+    None
+}
+
+/// Parameters of the DA1 answer in response.
+pub fn device_attributes(response: &str) -> Vec<&str> {
+    response
+        .rsplit_once("\x1b[?")
+        .and_then(|(_, attrs)| attrs.strip_suffix('c'))
+        .map(|attrs| attrs.split(';').collect())
+        .unwrap_or_default()
+}
@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::Render;
+use super::{Neighborhood, Render};
 
 static LEVEL10RAMP: &str = " .:-=+*#%@";
 static LEVEL70RAMP: &str =
@@ -49,7 +49,8 @@ impl AsciiRender {
 impl Render for AsciiRender {
     type Pixel = char;
 
-    fn render_pixel(&self, _up: u8, _left: u8, gray: u8, _right: u8, _down: u8) -> Self::Pixel {
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel {
+        let gray = n.center();
         let index = (f64::from(gray) * self.multiplier).floor() as usize;
         self.ramp[index]
     }
@@ -0,0 +1,333 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use grid::Grid;
+
+use super::{Neighborhood, Render};
+use crate::rasterizer::Bitmap;
+
+/// Glyph pixels are drawn in this gray, keep same as the foreground of color glyphs in TUI
+const INK: u8 = 0xC0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BlockRenders {
+    /// `▀` with 1x2 sub pixels
+    HalfBlock,
+    /// `▘▝▖▗` and their combinations, 2x2 sub pixels
+    Quadrant,
+    /// Unicode 13 sextants, 2x3 sub pixels
+    Sextant,
+}
+
+impl BlockRenders {
+    /// Sub pixels (cols, rows) in a cell.
+    pub fn cell_size(self) -> (usize, usize) {
+        match self {
+            Self::HalfBlock => (1, 2),
+            Self::Quadrant => (2, 2),
+            Self::Sextant => (2, 3),
+        }
+    }
+
+    /// Char whose sub pixels in `pattern` are foreground, bits are in row-major order.
+    fn char(self, pattern: usize) -> char {
+        match self {
+            Self::HalfBlock => [' ', '▀', '▄', '█'][pattern],
+            Self::Quadrant => [
+                ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+            ][pattern],
+            Self::Sextant => match pattern {
+                0 => ' ',
+                21 => '▌',
+                42 => '▐',
+                63 => '█',
+                // U+1FB00 starts from pattern 1, skips the 4 chars above
+                p => {
+                    let index = p - 1 - usize::from(p > 21) - usize::from(p > 42);
+                    char::from_u32(0x1FB00 + index as u32).expect("sextant chars are valid")
+                }
+            },
+        }
+    }
+}
+
+pub type Rgb = [u8; 3];
+
+/// A char with foreground and background color.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct BlockCell {
+    pub c: char,
+    pub fg: Rgb,
+    pub bg: Rgb,
+}
+
+impl BlockCell {
+    pub fn is_blank(&self) -> bool {
+        self.fg == [0; 3] && self.bg == [0; 3]
+    }
+}
+
+/// Terminal color depth used to show [`BlockCell`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+}
+
+impl ColorDepth {
+    /// Most terminals which support 24-bit color set `COLORTERM`
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => Self::TrueColor,
+            _ => Self::Ansi256,
+        }
+    }
+
+    /// Nearest color in xterm 256 color palette, from 6x6x6 cube or gray ramp.
+    pub fn ansi256(color: Rgb) -> u8 {
+        const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+        let cube_index = |v: u8| {
+            (0..6)
+                .min_by_key(|&i| CUBE[i].abs_diff(v))
+                .expect("cube is not empty")
+        };
+
+        let [r, g, b] = color.map(cube_index);
+        let cube = [CUBE[r], CUBE[g], CUBE[b]];
+
+        let avg = (color.iter().map(|&v| u32::from(v)).sum::<u32>() / 3) as u8;
+        let gray_index = (avg.saturating_sub(3) / 10).min(23);
+        let gray = 8 + gray_index * 10;
+
+        let distance = |c: Rgb| {
+            c.iter()
+                .zip(color)
+                .map(|(&a, b)| u32::from(a.abs_diff(b)).pow(2))
+                .sum::<u32>()
+        };
+
+        if distance([gray; 3]) < distance(cube) {
+            232 + gray_index
+        } else {
+            (16 + r * 36 + g * 6 + b) as u8
+        }
+    }
+}
+
+pub struct BlockRender {
+    kind: BlockRenders,
+}
+
+impl BlockRender {
+    pub fn new(kind: BlockRenders) -> Self {
+        Self { kind }
+    }
+
+    /// Color of a pixel on black background
+    fn color(bm: &Bitmap, row: usize, col: usize) -> Rgb {
+        let gray = bm.pixel(row, col);
+        let scale = |v: u8| (u16::from(v) * u16::from(gray) / 255) as u8;
+        match bm.colors() {
+            Some(colors) => {
+                let [r, g, b, _] = colors[(row, col)];
+                [scale(r), scale(g), scale(b)]
+            }
+            None => [scale(INK); 3],
+        }
+    }
+
+    /// Split sub pixels into foreground and background with the least error.
+    fn cell(&self, pixels: &[Rgb]) -> BlockCell {
+        let luminance = |c: &Rgb| c.iter().map(|&v| u32::from(v)).sum::<u32>();
+
+        let mean = |pattern: usize, fg: bool| {
+            let selected: Vec<_> = pixels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| (pattern >> i & 1 == 1) == fg)
+                .map(|(_, c)| *c)
+                .collect();
+            if selected.is_empty() {
+                return [0; 3];
+            }
+            let n = selected.len() as u32;
+            [0, 1, 2].map(|i| (selected.iter().map(|c| u32::from(c[i])).sum::<u32>() / n) as u8)
+        };
+
+        let error = |pattern: usize, fg: Rgb, bg: Rgb| {
+            pixels
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let target = if pattern >> i & 1 == 1 { fg } else { bg };
+                    luminance(c).abs_diff(luminance(&target)).pow(2)
+                })
+                .sum::<u32>()
+        };
+
+        // Only need to search half of patterns, the others are same with fg and bg swapped,
+        // and the brighter group is always used as foreground.
+        let best = (0..1usize << (pixels.len() - 1))
+            .map(|pattern| {
+                let (mut pattern, mut fg, mut bg) =
+                    (pattern, mean(pattern, true), mean(pattern, false));
+                if luminance(&fg) < luminance(&bg) {
+                    pattern = !pattern & ((1 << pixels.len()) - 1);
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+                (error(pattern, fg, bg), pattern, fg, bg)
+            })
+            .min_by_key(|&(error, ..)| error)
+            .expect("at least one pattern");
+
+        let (_, mut pattern, fg, bg) = best;
+        // All pixels are the same, a space with background is enough
+        if fg == bg {
+            pattern = 0;
+        }
+
+        BlockCell {
+            c: self.kind.char(pattern),
+            fg,
+            bg,
+        }
+    }
+}
+
+impl Render for BlockRender {
+    type Pixel = BlockCell;
+
+    /// Used only when a cell has one sub pixel
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel {
+        let gray = n.center();
+        let color = [(u16::from(INK) * u16::from(gray) / 255) as u8; 3];
+        BlockCell {
+            c: ' ',
+            fg: color,
+            bg: color,
+        }
+    }
+
+    fn render(&self, bm: &Bitmap) -> Grid<Self::Pixel> {
+        let m = bm.metrics();
+        let (cols, rows) = self.kind.cell_size();
+
+        let mut result = Grid::new(m.height.div_ceil(rows), m.width.div_ceil(cols));
+        let mut pixels = Vec::with_capacity(cols * rows);
+
+        for row in 0..result.rows() {
+            for col in 0..result.cols() {
+                pixels.clear();
+                for y in 0..rows {
+                    for x in 0..cols {
+                        let (r, c) = (row * rows + y, col * cols + x);
+                        pixels.push(if r < m.height && c < m.width {
+                            Self::color(bm, r, c)
+                        } else {
+                            [0; 3]
+                        });
+                    }
+                }
+                result[(row, col)] = self.cell(&pixels);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_block_chars() {
+        let chars: String = (0..4).map(|p| BlockRenders::HalfBlock.char(p)).collect();
+        assert_eq!(chars, " ▀▄█");
+    }
+
+    #[test]
+    fn quadrant_chars() {
+        for (pattern, c) in [
+            (0b0000, ' '),
+            (0b0001, '▘'),
+            (0b0010, '▝'),
+            (0b0100, '▖'),
+            (0b1000, '▗'),
+            (0b0101, '▌'),
+            (0b1010, '▐'),
+            (0b0110, '▞'),
+            (0b1001, '▚'),
+            (0b1110, '▟'),
+            (0b1111, '█'),
+        ] {
+            assert_eq!(
+                BlockRenders::Quadrant.char(pattern),
+                c,
+                "pattern {pattern:04b}"
+            );
+        }
+    }
+
+    #[test]
+    fn sextant_chars() {
+        // Sextant n is bit n-1, the name of U+1FBxx lists its sextants
+        for (pattern, c) in [
+            (0b000000, ' '),
+            (0b000001, '\u{1FB00}'), // SEXTANT-1
+            (0b000010, '\u{1FB01}'), // SEXTANT-2
+            (0b010100, '\u{1FB13}'), // SEXTANT-35
+            (0b010101, '▌'),
+            (0b010110, '\u{1FB14}'), // SEXTANT-235
+            (0b101001, '\u{1FB27}'), // SEXTANT-146
+            (0b101010, '▐'),
+            (0b101011, '\u{1FB28}'), // SEXTANT-1246
+            (0b111110, '\u{1FB3B}'), // SEXTANT-23456
+            (0b111111, '█'),
+        ] {
+            assert_eq!(
+                BlockRenders::Sextant.char(pattern),
+                c,
+                "pattern {pattern:06b}"
+            );
+        }
+    }
+
+    #[test]
+    fn ansi256() {
+        assert_eq!(ColorDepth::ansi256([0, 0, 0]), 16);
+        assert_eq!(ColorDepth::ansi256([255, 255, 255]), 231);
+        assert_eq!(ColorDepth::ansi256([255, 0, 0]), 196);
+        assert_eq!(ColorDepth::ansi256([95, 135, 175]), 67);
+        assert_eq!(ColorDepth::ansi256([8, 8, 8]), 232);
+        assert_eq!(ColorDepth::ansi256([128, 128, 128]), 244);
+        assert_eq!(ColorDepth::ansi256([238, 238, 238]), 255);
+    }
+
+    #[test]
+    fn split_cell() {
+        let render = BlockRender::new(BlockRenders::HalfBlock);
+        let cell = render.cell(&[[0; 3], [192; 3]]);
+        assert_eq!((cell.c, cell.fg, cell.bg), ('▄', [192; 3], [0; 3]));
+
+        let render = BlockRender::new(BlockRenders::Quadrant);
+        // Flat cell uses the empty pattern swapped, a full block on black
+        let cell = render.cell(&[[100; 3]; 4]);
+        assert_eq!((cell.c, cell.fg, cell.bg), ('█', [100; 3], [0; 3]));
+    }
+}
@@ -0,0 +1,165 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::{Neighborhood, Render};
+
+/// Pixels at least this gray are ink
+const INK: u8 = 128;
+
+#[derive(Copy, Clone)]
+pub enum EdgeRenders {
+    /// `─ │ ╱ ╲ ┌ ┐ └ ┘`
+    BoxDrawing,
+    /// `- _ | / \ +`
+    Ascii,
+}
+
+/// Draws contour of glyph, char of each edge pixel is picked by local gradient direction.
+pub struct EdgeRender {
+    kind: EdgeRenders,
+}
+
+impl EdgeRender {
+    pub fn new(kind: EdgeRenders) -> Self {
+        Self { kind }
+    }
+
+    /// Corner chars in order of top left, top right, bottom left, bottom right
+    fn corners(&self) -> [char; 4] {
+        match self.kind {
+            EdgeRenders::BoxDrawing => ['┌', '┐', '└', '┘'],
+            EdgeRenders::Ascii => ['+'; 4],
+        }
+    }
+
+    /// Corner of a shape, whose two sides are both straight.
+    fn corner(&self, n: &Neighborhood) -> Option<char> {
+        let ink = |dy, dx| n.at(dy, dx) >= INK;
+        let [top_left, top_right, bottom_left, bottom_right] = self.corners();
+
+        // Blank diagonal neighbors tell corner from a diagonal edge
+        match (ink(-1, 0), ink(1, 0), ink(0, -1), ink(0, 1)) {
+            (false, true, false, true) if !ink(-1, 1) && !ink(1, -1) => Some(top_left),
+            (false, true, true, false) if !ink(-1, -1) && !ink(1, 1) => Some(top_right),
+            (true, false, false, true) if !ink(-1, -1) && !ink(1, 1) => Some(bottom_left),
+            (true, false, true, false) if !ink(-1, 1) && !ink(1, -1) => Some(bottom_right),
+            _ => None,
+        }
+    }
+
+    /// Edge along the contour, which is perpendicular to the gradient.
+    fn edge(&self, n: &Neighborhood) -> char {
+        let p = |dy, dx| i32::from(n.at(dy, dx));
+        // Sobel operator, y axis points down
+        let gx = p(-1, 1) + 2 * p(0, 1) + p(1, 1) - p(-1, -1) - 2 * p(0, -1) - p(1, -1);
+        let gy = p(1, -1) + 2 * p(1, 0) + p(1, 1) - p(-1, -1) - 2 * p(-1, 0) - p(-1, 1);
+
+        let ascii = matches!(self.kind, EdgeRenders::Ascii);
+        if gx.abs() > 2 * gy.abs() {
+            if ascii { '|' } else { '│' }
+        } else if gy.abs() > 2 * gx.abs() {
+            match (ascii, gy < 0) {
+                (false, _) => '─',
+                // Ink is above, so this is the bottom side
+                (true, true) => '_',
+                (true, false) => '-',
+            }
+        } else if (gx > 0) == (gy > 0) {
+            if ascii { '/' } else { '╱' }
+        } else if ascii {
+            '\\'
+        } else {
+            '╲'
+        }
+    }
+}
+
+impl Render for EdgeRender {
+    type Pixel = char;
+
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel {
+        let blank = |gray: u8| gray < INK;
+
+        // Only ink pixels next to blank ones are on the contour
+        let on_contour = !blank(n.center())
+            && [n.up(), n.down(), n.left(), n.right()]
+                .into_iter()
+                .any(blank);
+        if !on_contour {
+            return ' ';
+        }
+
+        self.corner(n).unwrap_or_else(|| self.edge(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x3 neighborhood from rows of `#` (ink) and `.` (blank).
+    fn n(rows: [&str; 3]) -> Neighborhood {
+        let pixels = rows.map(|row| {
+            let mut line = [0; 3];
+            for (pixel, c) in line.iter_mut().zip(row.chars()) {
+                *pixel = if c == '#' { 255 } else { 0 };
+            }
+            line
+        });
+        Neighborhood { pixels }
+    }
+
+    fn chars(rows: [&str; 3]) -> (char, char) {
+        let n = n(rows);
+        (
+            EdgeRender::new(EdgeRenders::BoxDrawing).render_pixel(&n),
+            EdgeRender::new(EdgeRenders::Ascii).render_pixel(&n),
+        )
+    }
+
+    #[test]
+    fn horizontal() {
+        assert_eq!(chars(["...", "###", "###"]), ('─', '-'));
+        assert_eq!(chars(["###", "###", "..."]), ('─', '_'));
+    }
+
+    #[test]
+    fn vertical() {
+        assert_eq!(chars(["##.", "##.", "##."]), ('│', '|'));
+        assert_eq!(chars([".##", ".##", ".##"]), ('│', '|'));
+    }
+
+    #[test]
+    fn diagonal() {
+        assert_eq!(chars(["..#", ".##", "###"]), ('╱', '/'));
+        assert_eq!(chars(["#..", "##.", "###"]), ('╲', '\\'));
+        assert_eq!(chars(["###", "##.", "#.."]), ('╱', '/'));
+    }
+
+    #[test]
+    fn corners() {
+        assert_eq!(chars(["...", ".##", ".##"]), ('┌', '+'));
+        assert_eq!(chars(["##.", "##.", "..."]), ('┘', '+'));
+    }
+
+    #[test]
+    fn not_on_contour() {
+        assert_eq!(chars(["###", "###", "###"]), (' ', ' '));
+        assert_eq!(chars(["###", "#.#", "###"]), (' ', ' '));
+    }
+}
@@ -17,23 +17,138 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 mod ascii;
+mod block;
+mod edge;
 mod mono;
 mod moon;
 
+use clap::ValueEnum;
 use grid::Grid;
 
 pub use self::{
     ascii::{AsciiRender, AsciiRenders},
-    mono::MonoRender,
+    block::{BlockCell, BlockRender, BlockRenders, ColorDepth, Rgb},
+    edge::{EdgeRender, EdgeRenders},
+    mono::{Dither, MonoRender},
     moon::MoonRender,
 };
 use crate::rasterizer::Bitmap;
 
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash, ValueEnum)]
+pub enum RenderType {
+    #[value(name = "ascii10")]
+    AsciiLevel10,
+    #[value(name = "ascii70")]
+    AsciiLevel70,
+    Moon,
+    Mono,
+    #[value(name = "half")]
+    HalfBlock,
+    Quadrant,
+    Sextant,
+    Edge,
+    #[value(name = "edge-ascii")]
+    EdgeAscii,
+    /// Real image through terminal graphics protocol, TUI only
+    #[value(skip)]
+    Image,
+    /// Contours, points and directions of glyph outline, TUI only
+    #[value(skip)]
+    Outline,
+}
+
+impl RenderType {
+    /// Block render used by this type, if any.
+    pub fn block(self) -> Option<BlockRenders> {
+        match self {
+            Self::HalfBlock => Some(BlockRenders::HalfBlock),
+            Self::Quadrant => Some(BlockRenders::Quadrant),
+            Self::Sextant => Some(BlockRenders::Sextant),
+            _ => None,
+        }
+    }
+
+    /// Bitmap pixels (cols, rows) shown in one terminal cell.
+    pub fn cell_size(self) -> (u32, u32) {
+        match self {
+            // Outline is drawn on braille canvas too
+            Self::Mono | Self::Outline => (2, 4),
+            rt => rt.block().map_or((1, 1), |b| {
+                let (cols, rows) = b.cell_size();
+                (cols as u32, rows as u32)
+            }),
+        }
+    }
+
+    /// Horizontal scale to make pixels look square, `aspect` is cell height divided by width.
+    pub fn hscale(self, aspect: f32) -> f32 {
+        match self {
+            // Moon emoji are double width
+            Self::Moon => aspect / 2.0,
+            // Image pixels are square
+            Self::Image => 1.0,
+            rt => {
+                let (cols, rows) = rt.cell_size();
+                aspect * cols as f32 / rows as f32
+            }
+        }
+    }
+}
+
+/// A pixel and its 8 neighbors, pixels outside of bitmap are 0.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Neighborhood {
+    pixels: [[u8; 3]; 3],
+}
+
+impl Neighborhood {
+    pub fn new(bm: &Bitmap, row: usize, col: usize) -> Self {
+        let m = bm.metrics();
+        let mut pixels = [[0; 3]; 3];
+        for (dy, line) in pixels.iter_mut().enumerate() {
+            for (dx, pixel) in line.iter_mut().enumerate() {
+                let (Some(r), Some(c)) = ((row + dy).checked_sub(1), (col + dx).checked_sub(1))
+                else {
+                    continue;
+                };
+                if r < m.height && c < m.width {
+                    *pixel = bm.pixel(r, c);
+                }
+            }
+        }
+        Self { pixels }
+    }
+
+    /// Pixel at offset (`dy`, `dx`) from center, both in `-1..=1`.
+    pub fn at(&self, dy: isize, dx: isize) -> u8 {
+        self.pixels[(dy + 1) as usize][(dx + 1) as usize]
+    }
+
+    pub fn center(&self) -> u8 {
+        self.at(0, 0)
+    }
+
+    pub fn up(&self) -> u8 {
+        self.at(-1, 0)
+    }
+
+    pub fn down(&self) -> u8 {
+        self.at(1, 0)
+    }
+
+    pub fn left(&self) -> u8 {
+        self.at(0, -1)
+    }
+
+    pub fn right(&self) -> u8 {
+        self.at(0, 1)
+    }
+}
+
 pub trait Render {
     type Pixel: Default;
 
-    #[allow(clippy::too_many_arguments)] // need them..., fine, I will try make them a struct
-    fn render_pixel(&self, up: u8, left: u8, gray: u8, right: u8, down: u8) -> Self::Pixel;
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel;
 
     fn render(&self, bm: &Bitmap) -> Grid<Self::Pixel> {
         let m = bm.metrics();
@@ -42,22 +157,7 @@ pub trait Render {
 
         for row in 0..m.height {
             for col in 0..m.width {
-                let gray = bm.pixel(row, col);
-
-                let l = if col > 0 { bm.pixel(row, col - 1) } else { 0 };
-                let r = if col < m.width - 1 {
-                    bm.pixel(row, col + 1)
-                } else {
-                    0
-                };
-                let u = if row > 0 { bm.pixel(row - 1, col) } else { 0 };
-                let d = if row < m.height - 1 {
-                    bm.pixel(row + 1, col)
-                } else {
-                    0
-                };
-
-                result[(row, col)] = self.render_pixel(u, l, gray, r, d)
+                result[(row, col)] = self.render_pixel(&Neighborhood::new(bm, row, col))
             }
         }
 
@@ -16,15 +16,196 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::Render;
+use grid::Grid;
 
-#[derive(Default)]
-pub struct MonoRender {}
+use super::{Neighborhood, Render};
+use crate::rasterizer::Bitmap;
+
+/// 4x4 Bayer matrix for ordered dithering
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Neighbors (dy, dx) and weight of diffused error, weights of a method sum to its divisor
+const FLOYD_STEINBERG: &[(usize, isize, f32)] =
+    &[(0, 1, 7.0), (1, -1, 3.0), (1, 0, 5.0), (1, 1, 1.0)];
+/// Atkinson only diffuses 3/4 of the error, keeps thin strokes crisp
+const ATKINSON: &[(usize, isize, f32)] = &[
+    (0, 1, 1.0),
+    (0, 2, 1.0),
+    (1, -1, 1.0),
+    (1, 0, 1.0),
+    (1, 1, 1.0),
+    (2, 0, 1.0),
+];
+
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Dither {
+    /// Hard threshold
+    #[default]
+    None,
+    FloydSteinberg,
+    Atkinson,
+    Bayer,
+}
+
+impl Dither {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::FloydSteinberg => "Floyd-Steinberg",
+            Self::Atkinson => "Atkinson",
+            Self::Bayer => "Bayer",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::FloydSteinberg,
+            Self::FloydSteinberg => Self::Atkinson,
+            Self::Atkinson => Self::Bayer,
+            Self::Bayer => Self::None,
+        }
+    }
+}
+
+pub struct MonoRender {
+    threshold: u8,
+    dither: Dither,
+}
+
+impl Default for MonoRender {
+    fn default() -> Self {
+        Self::new(128, Dither::None)
+    }
+}
+
+impl MonoRender {
+    pub fn new(threshold: u8, dither: Dither) -> Self {
+        Self { threshold, dither }
+    }
+
+    /// Error diffusion, the quantization error of each pixel is spread to its unvisited neighbors
+    fn diffuse(&self, bm: &Bitmap, kernel: &[(usize, isize, f32)], divisor: f32) -> Grid<bool> {
+        let m = bm.metrics();
+        let mut values = Grid::init(m.height, m.width, 0.0f32);
+        for row in 0..m.height {
+            for col in 0..m.width {
+                values[(row, col)] = f32::from(bm.pixel(row, col));
+            }
+        }
+
+        let mut result = Grid::new(m.height, m.width);
+        for row in 0..m.height {
+            for col in 0..m.width {
+                let value = values[(row, col)];
+                let on = value >= f32::from(self.threshold);
+                result[(row, col)] = on;
+
+                let error = value - if on { 255.0 } else { 0.0 };
+                for &(dy, dx, weight) in kernel {
+                    let Some(x) = col.checked_add_signed(dx) else {
+                        continue;
+                    };
+                    if let Some(v) = values.get_mut(row + dy, x) {
+                        *v += error * weight / divisor;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Threshold of each pixel is moved by `offset(row, col)` of full range
+    fn ordered<F>(&self, bm: &Bitmap, offset: F) -> Grid<bool>
+    where
+        F: Fn(usize, usize) -> f32,
+    {
+        let m = bm.metrics();
+        let mut result = Grid::new(m.height, m.width);
+        for row in 0..m.height {
+            for col in 0..m.width {
+                let threshold = f32::from(self.threshold) + offset(row, col) * 255.0;
+                result[(row, col)] = f32::from(bm.pixel(row, col)) >= threshold;
+            }
+        }
+        result
+    }
+}
 
 impl Render for MonoRender {
     type Pixel = bool;
 
-    fn render_pixel(&self, _up: u8, _left: u8, gray: u8, _right: u8, _down: u8) -> Self::Pixel {
-        gray >= 128
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel {
+        let gray = n.center();
+        gray >= self.threshold
+    }
+
+    fn render(&self, bm: &Bitmap) -> Grid<Self::Pixel> {
+        match self.dither {
+            Dither::None => self.ordered(bm, |_, _| 0.0),
+            Dither::FloydSteinberg => self.diffuse(bm, FLOYD_STEINBERG, 16.0),
+            Dither::Atkinson => self.diffuse(bm, ATKINSON, 8.0),
+            Dither::Bayer => self.ordered(bm, |row, col| {
+                (f32::from(BAYER[row % 4][col % 4]) + 0.5) / 16.0 - 0.5
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DITHERS: [Dither; 4] = [
+        Dither::None,
+        Dither::FloydSteinberg,
+        Dither::Atkinson,
+        Dither::Bayer,
+    ];
+
+    fn flat(gray: u8) -> Bitmap {
+        Bitmap::from_alpha(Grid::init(16, 16, [0, 0, 0, gray]), (0, 0))
+    }
+
+    fn dots(render: &MonoRender, bm: &Bitmap) -> usize {
+        render.render(bm).iter().filter(|&&on| on).count()
+    }
+
+    #[test]
+    fn half_gray_sets_half_dots() {
+        let bm = flat(128);
+        for dither in DITHERS.into_iter().skip(1) {
+            let count = dots(&MonoRender::new(128, dither), &bm);
+            assert!(
+                (120..=136).contains(&count),
+                "{dither:?} sets {count} of 256 dots"
+            );
+        }
+    }
+
+    #[test]
+    fn extreme_thresholds() {
+        let bm = flat(128);
+        // Error diffusion keeps average brightness, so only threshold based methods are checked
+        for dither in [Dither::None, Dither::Bayer] {
+            assert_eq!(dots(&MonoRender::new(0, dither), &bm), 256, "{dither:?}");
+            assert_eq!(dots(&MonoRender::new(255, dither), &bm), 0, "{dither:?}");
+        }
+    }
+
+    #[test]
+    fn full_and_empty() {
+        for dither in DITHERS {
+            assert_eq!(
+                dots(&MonoRender::new(128, dither), &flat(255)),
+                256,
+                "{dither:?}"
+            );
+            assert_eq!(
+                dots(&MonoRender::new(128, dither), &flat(1)),
+                0,
+                "{dither:?}"
+            );
+        }
     }
 }
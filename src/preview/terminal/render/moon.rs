@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::Render;
+use super::{Neighborhood, Render};
 
 static MOON_CHARS: [[char; 2]; 5] = [
     ['🌕', '🌕'],
@@ -40,14 +40,15 @@ impl MoonRender {
 impl Render for MoonRender {
     type Pixel = char;
 
-    fn render_pixel(&self, _up: u8, left: u8, gray: u8, right: u8, _down: u8) -> Self::Pixel {
+    fn render_pixel(&self, n: &Neighborhood) -> Self::Pixel {
+        let gray = n.center();
         if gray == 0 {
             return MOON_CHARS[MOON_CHARS.len() - 1][0];
         }
 
         let index = (f64::from(255 - gray) * self.multiplier).floor() as usize;
 
-        if left < right {
+        if n.left() < n.right() {
             MOON_CHARS[index][1]
         } else {
             MOON_CHARS[index][0]
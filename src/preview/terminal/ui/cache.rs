@@ -20,19 +20,24 @@ use std::{collections::HashMap, iter::Iterator, sync::LazyLock};
 
 use grid::Grid;
 use ratatui::{
-    style::Color,
-    widgets::canvas::{Painter, Shape},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::canvas::{Context, Line as CanvasLine, Painter, Shape},
 };
 
-use crate::preview::terminal::render::{AsciiRender, AsciiRenders, MonoRender, MoonRender, Render};
-
-#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
-pub enum RenderType {
-    AsciiLevel10,
-    AsciiLevel70,
-    Moon,
-    Mono,
-}
+pub use crate::preview::terminal::render::RenderType;
+use crate::{
+    export::{self, source_over},
+    loader::{GlyphOutline, Point, Segment},
+    preview::terminal::{
+        graphics::Protocol,
+        render::{
+            AsciiRender, AsciiRenders, BlockCell, ColorDepth, Dither, EdgeRender, EdgeRenders,
+            MoonRender, Render, Rgb,
+        },
+    },
+    rasterizer::{Bitmap, Guide, GuideKind, Overlap, Rgba},
+};
 
 type BoxedRender<Pixel> = Box<dyn Render<Pixel = Pixel> + Send + Sync>;
 
@@ -47,22 +52,125 @@ pub static CHAR_RENDERS: LazyLock<HashMap<RenderType, BoxedRender<char>>> = Lazy
         Box::new(AsciiRender::new(AsciiRenders::Level70)),
     );
     renders.insert(RenderType::Moon, Box::new(MoonRender::new()));
+    renders.insert(
+        RenderType::Edge,
+        Box::new(EdgeRender::new(EdgeRenders::BoxDrawing)),
+    );
+    renders.insert(
+        RenderType::EdgeAscii,
+        Box::new(EdgeRender::new(EdgeRenders::Ascii)),
+    );
     renders
 });
 
-pub static MONO_RENDER: LazyLock<MonoRender> = LazyLock::new(MonoRender::default);
-
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct CacheKey {
     pub index: (usize, usize),
     pub rt: RenderType,
     pub width: u32,
     pub height: u32,
+    pub variation: Vec<u32>,
+    pub em_box: bool,
+    /// Bits of horizontal scale, which changes with cell aspect ratio
+    pub hscale: u32,
+    pub threshold: u8,
+    pub dither: Dither,
+    /// Bits of ascender and descender in ems shared by compared faces
+    pub shared_em: Option<[u32; 2]>,
+    /// Reference face overlaid on the glyph
+    pub reference: Option<(usize, usize)>,
+    /// Pixel height of glyph in waterfall view
+    pub size: Option<u32>,
+    /// Bits of zoom factor
+    pub zoom: u32,
 }
 
 pub enum GlyphCache {
     Canvas(GlyphCanvasShape),
     Paragraph(GlyphParagraph),
+    Image(GlyphImage),
+    Outline(GlyphOutlineShape),
+}
+
+/// Render result of a glyph and where it comes from.
+pub struct CachedGlyph {
+    pub content: GlyphCache,
+    /// Embedded bitmap strike size used, `None` for outline glyph
+    pub strike: Option<u16>,
+    /// Pixels of glyph and reference glyph in overlay view
+    pub overlap: Option<Overlap>,
+}
+
+/// Terminal color of a pixel in color glyph, transparent pixel uses default color
+fn terminal_color(colors: Option<&Grid<Rgba>>, row: usize, col: usize) -> Color {
+    match colors.map(|c| c[(row, col)]) {
+        Some([r, g, b, a]) if a > 0 => Color::Rgb(r, g, b),
+        _ => Color::Reset,
+    }
+}
+
+pub fn guide_color(kind: GuideKind) -> Color {
+    match kind {
+        GuideKind::Ascender | GuideKind::Descender => Color::Blue,
+        GuideKind::CapHeight => Color::Yellow,
+        GuideKind::XHeight => Color::Green,
+        GuideKind::Baseline => Color::Red,
+        GuideKind::Origin | GuideKind::Advance => Color::Magenta,
+    }
+}
+
+/// Pixel colors of only glyph, only reference glyph and both in overlay view.
+pub const OVERLAY_COLORS: [Rgba; 3] = [[0xF0, 0x50, 0x50, 0xFF], [0x50, 0xA0, 0xF0, 0xFF], [
+    0xC0, 0xC0, 0xC0, 0xFF,
+]];
+
+/// Same colors as [`guide_color`], for images.
+fn guide_rgba(kind: GuideKind) -> Rgba {
+    match kind {
+        GuideKind::Ascender | GuideKind::Descender => [0x40, 0x80, 0xFF, 0xFF],
+        GuideKind::CapHeight => [0xFF, 0xD0, 0x40, 0xFF],
+        GuideKind::XHeight => [0x40, 0xD0, 0x40, 0xFF],
+        GuideKind::Baseline => [0xFF, 0x40, 0x40, 0xFF],
+        GuideKind::Origin | GuideKind::Advance => [0xE0, 0x40, 0xE0, 0xFF],
+    }
+}
+
+/// Guide line which covers the cell, the last one wins if there are many.
+fn guide_at(guides: &[Guide], row: usize, col: usize) -> Option<&Guide> {
+    guides.iter().rev().find(|g| {
+        if g.kind.is_vertical() {
+            g.position == col
+        } else {
+            g.position == row
+        }
+    })
+}
+
+/// Position of a guide line, `None` if there is no such guide.
+fn guide_position(guides: &[Guide], kind: GuideKind) -> Option<f64> {
+    guides
+        .iter()
+        .find(|g| g.kind == kind)
+        .map(|g| g.position as f64)
+}
+
+/// Padding (horizontal, vertical) to put bitmap at center of canvas.
+///
+/// Center the em box if exists, so overflowed part of glyph do not move it
+fn padding(source: &Bitmap, canvas_width: f64, canvas_height: f64) -> (f64, f64) {
+    let guides = source.guides();
+    let m = source.metrics();
+
+    let (top, bottom) = guide_position(guides, GuideKind::Ascender)
+        .zip(guide_position(guides, GuideKind::Descender))
+        .unwrap_or((0.0, m.height as f64 - 1.0));
+    let (left, right) = guide_position(guides, GuideKind::Origin)
+        .zip(guide_position(guides, GuideKind::Advance))
+        .unwrap_or((0.0, m.width as f64 - 1.0));
+
+    let h_pad = ((canvas_width - (right - left + 1.0)) / 2.0).floor() - left;
+    let v_pad = ((canvas_height - (bottom - top + 1.0)) / 2.0).floor() - top;
+    (h_pad, v_pad)
 }
 
 pub struct GlyphCanvasShape {
@@ -70,20 +178,35 @@ pub struct GlyphCanvasShape {
     v_pad: f64,
     canvas_height: f64,
     bitmap: Grid<bool>,
+    colors: Option<Grid<Rgba>>,
+    guides: Vec<Guide>,
 }
 
 impl GlyphCanvasShape {
-    pub fn new(bitmap: Grid<bool>, canvas_width: f64, canvas_height: f64) -> Self {
-        let h_pad = ((canvas_width - bitmap.cols() as f64) / 2.0).floor();
-        let v_pad = ((canvas_height - bitmap.rows() as f64) / 2.0).floor();
+    pub fn new(bitmap: Grid<bool>, source: &Bitmap, canvas_width: f64, canvas_height: f64) -> Self {
+        let guides = source.guides();
+        let (h_pad, v_pad) = padding(source, canvas_width, canvas_height);
         Self {
             h_pad,
             v_pad,
             canvas_height,
             bitmap,
+            colors: source.colors().cloned(),
+            guides: guides.to_vec(),
         }
     }
 
+    /// Canvas point of a bitmap pixel.
+    ///
+    /// tui canvas origin point at left bottom but chars' at left top
+    /// so we need do some math to flip it and add padding
+    fn point(&self, row: usize, col: usize) -> (f64, f64) {
+        (
+            col as f64 + self.h_pad,
+            self.canvas_height - row as f64 - self.v_pad,
+        )
+    }
+
     fn points(&self) -> GlyphCanvasShapePoints<'_> {
         GlyphCanvasShapePoints::new(self)
     }
@@ -121,7 +244,7 @@ impl<'a> GlyphCanvasShapePoints<'a> {
 }
 
 impl Iterator for GlyphCanvasShapePoints<'_> {
-    type Item = (f64, f64);
+    type Item = (f64, f64, Color);
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
@@ -129,13 +252,9 @@ impl Iterator for GlyphCanvasShapePoints<'_> {
                 return None;
             }
             if self.shape.bitmap[(self.y, self.x)] {
-                // tui canvas origin point at left bottom but chars' at left top
-                // so we need do some math to flip it and add padding
-                let result = (
-                    self.x as f64 + self.shape.h_pad,
-                    self.shape.canvas_height - self.y as f64 - self.shape.v_pad,
-                );
-                return Some(result);
+                let (x, y) = self.shape.point(self.y, self.x);
+                let color = terminal_color(self.shape.colors.as_ref(), self.y, self.x);
+                return Some((x, y, color));
             }
         }
     }
@@ -143,21 +262,337 @@ impl Iterator for GlyphCanvasShapePoints<'_> {
 
 impl Shape for GlyphCanvasShape {
     fn draw(&self, painter: &mut Painter<'_, '_>) {
-        for (x, y) in self.points() {
+        // Guides are painted first, so glyph is always on top
+        for guide in &self.guides {
+            let (rows, cols) = if guide.kind.is_vertical() {
+                (0..self.bitmap.rows(), guide.position..guide.position + 1)
+            } else {
+                (guide.position..guide.position + 1, 0..self.bitmap.cols())
+            };
+            for row in rows {
+                for col in cols.clone() {
+                    let (x, y) = self.point(row, col);
+                    if let Some((x, y)) = painter.get_point(x, y) {
+                        painter.paint(x, y, guide_color(guide.kind));
+                    }
+                }
+            }
+        }
+
+        for (x, y, color) in self.points() {
             if let Some((x, y)) = painter.get_point(x, y) {
-                painter.paint(x, y, Color::Reset);
+                painter.paint(x, y, color);
             }
         }
     }
 }
 
 pub struct GlyphParagraph {
-    pub lines: Vec<String>,
+    pub lines: Vec<Line<'static>>,
 }
 
 impl GlyphParagraph {
-    pub fn new(bitmap: Grid<char>) -> Self {
-        let lines = bitmap.iter_rows().map(String::from_iter).collect();
+    pub fn new(chars: Grid<char>, source: &Bitmap) -> Self {
+        let colors = source.colors();
+        let guides = source.guides();
+
+        let lines = chars
+            .iter_rows()
+            .enumerate()
+            .map(|(row, chars)| {
+                if colors.is_none() && guides.is_empty() {
+                    return Line::from(String::from_iter(chars));
+                }
+
+                chars
+                    .enumerate()
+                    .map(|(col, c)| {
+                        let span = Span::raw(c.to_string());
+                        match guide_at(guides, row, col) {
+                            // Only draw guide in blank area, keep the width of replaced char
+                            Some(guide) if source.pixel(row, col) == 0 => {
+                                let line = if guide.kind.is_vertical() {
+                                    "│"
+                                } else {
+                                    "─"
+                                };
+                                Span::styled(
+                                    line.repeat(span.width()),
+                                    Style::default().fg(guide_color(guide.kind)),
+                                )
+                            }
+                            _ => span.style(Style::default().fg(terminal_color(colors, row, col))),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { lines }
+    }
+
+    /// Colored block cells, each cell covers `cell_size` (cols, rows) bitmap pixels.
+    pub fn from_cells(
+        cells: Grid<BlockCell>, source: &Bitmap, cell_size: (usize, usize), depth: ColorDepth,
+    ) -> Self {
+        let guides = source.guides();
+        let (cell_cols, cell_rows) = cell_size;
+
+        let color = |rgb: Rgb| match depth {
+            ColorDepth::TrueColor => Color::Rgb(rgb[0], rgb[1], rgb[2]),
+            ColorDepth::Ansi256 => Color::Indexed(ColorDepth::ansi256(rgb)),
+        };
+
+        let lines = cells
+            .iter_rows()
+            .enumerate()
+            .map(|(row, cells)| {
+                cells
+                    .enumerate()
+                    .map(|(col, cell)| {
+                        let guide = guides.iter().rev().find(|g| {
+                            if g.kind.is_vertical() {
+                                g.position / cell_cols == col
+                            } else {
+                                g.position / cell_rows == row
+                            }
+                        });
+                        match guide {
+                            // Only draw guide in blank area
+                            Some(guide) if cell.is_blank() => {
+                                let line = if guide.kind.is_vertical() {
+                                    "│"
+                                } else {
+                                    "─"
+                                };
+                                Span::styled(line, Style::default().fg(guide_color(guide.kind)))
+                            }
+                            _ if cell.is_blank() => Span::raw(" "),
+                            _ => Span::styled(
+                                cell.c.to_string(),
+                                Style::default().fg(color(cell.fg)).bg(color(cell.bg)),
+                            ),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
         Self { lines }
     }
 }
+
+/// Glyph image in terminal graphics protocol escape sequence.
+pub struct GlyphImage {
+    pub data: String,
+}
+
+impl GlyphImage {
+    pub fn new(source: &Bitmap, width: u32, height: u32, protocol: Protocol) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let mut canvas = Grid::init(height, width, [0; 4]);
+
+        let (h_pad, v_pad) = padding(source, width as f64, height as f64);
+        let (h_pad, v_pad) = (h_pad as isize, v_pad as isize);
+        let mut paint = |row: usize, col: usize, color: Rgba| {
+            // Negative positions are out of canvas
+            if let Some(pixel) = canvas.get_mut(row as isize + v_pad, col as isize + h_pad) {
+                *pixel = source_over(color, *pixel);
+            }
+        };
+
+        let m = source.metrics();
+        for guide in source.guides() {
+            let color = guide_rgba(guide.kind);
+            if guide.kind.is_vertical() {
+                (0..m.height).for_each(|row| paint(row, guide.position, color));
+            } else {
+                (0..m.width).for_each(|col| paint(guide.position, col, color));
+            }
+        }
+
+        let glyph = export::composite(source, [0xC0, 0xC0, 0xC0, 0xFF], [0; 4]);
+        for (row, pixels) in glyph.iter_rows().enumerate() {
+            for (col, pixel) in pixels.enumerate() {
+                paint(row, col, *pixel);
+            }
+        }
+
+        Self {
+            data: protocol.encode(&canvas),
+        }
+    }
+}
+
+/// Arrow char points to direction (`dx`, `dy`), y axis goes up.
+fn arrow(dx: f32, dy: f32) -> char {
+    const ARROWS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+    let octant = (dy.atan2(dx) / std::f32::consts::FRAC_PI_4).round() as i32;
+    ARROWS[octant.rem_euclid(8) as usize]
+}
+
+/// How to fit outline into canvas.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutlineFit {
+    /// Bounds of points
+    Points,
+    /// Em box and points outside of it
+    EmBox,
+    /// Shared ascender and descender in ems, glyph may overflow horizontally
+    SharedEm(f32, f32),
+}
+
+/// How large outline is drawn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutlineSize {
+    /// Fit into canvas by [`OutlineFit`], then scaled by this factor
+    Zoom(f64),
+    /// Canvas pixels from ascender to descender, same as rasterized glyph of this pixel height
+    Pixels(f64),
+}
+
+/// Contours of glyph with their points, drawn on braille canvas.
+pub struct GlyphOutlineShape {
+    outline: GlyphOutline,
+    em_box: bool,
+    /// Canvas pixels per font unit, (x, y)
+    scale: (f64, f64),
+    /// Canvas position of font unit origin
+    origin: (f64, f64),
+}
+
+impl GlyphOutlineShape {
+    /// Put outline in canvas by `fit` and `size`. `hscale` makes canvas pixels look square.
+    pub fn new(
+        outline: GlyphOutline, (canvas_width, canvas_height): (f64, f64), hscale: f64,
+        fit: OutlineFit, size: OutlineSize,
+    ) -> Self {
+        let bounds = match fit {
+            OutlineFit::Points => outline.point_bounds().unwrap_or_default(),
+            OutlineFit::EmBox => outline.em_bounds(),
+            OutlineFit::SharedEm(ascender, descender) => {
+                let [x_min, _, x_max, _] = outline.em_bounds();
+                let upem = f32::from(outline.units_per_em);
+                [x_min, descender * upem, x_max, ascender * upem]
+            }
+        };
+        let [x_min, y_min, x_max, y_max] = bounds.map(f64::from);
+        let (width, height) = ((x_max - x_min).max(1.0), (y_max - y_min).max(1.0));
+
+        // Leave some space for point marks at border
+        let fitted = || match fit {
+            OutlineFit::SharedEm(..) => canvas_height * 0.9 / height,
+            _ => (canvas_height * 0.9 / height).min(canvas_width * 0.9 / (width * hscale)),
+        };
+        let k = match size {
+            OutlineSize::Zoom(zoom) => fitted() * zoom,
+            OutlineSize::Pixels(pixels) => {
+                let line_height = match fit {
+                    OutlineFit::SharedEm(ascender, descender) => {
+                        f64::from((ascender - descender) * f32::from(outline.units_per_em))
+                    }
+                    _ => f64::from(outline.ascender) - f64::from(outline.descender),
+                };
+                pixels / line_height.max(1.0)
+            }
+        };
+        let scale = (k * hscale, k);
+        let origin = (
+            (canvas_width - width * scale.0) / 2.0 - x_min * scale.0,
+            (canvas_height - height * scale.1) / 2.0 - y_min * scale.1,
+        );
+
+        Self {
+            outline,
+            em_box: fit != OutlineFit::Points,
+            scale,
+            origin,
+        }
+    }
+
+    pub fn outline(&self) -> &GlyphOutline {
+        &self.outline
+    }
+
+    fn point(&self, p: Point) -> (f64, f64) {
+        (
+            self.origin.0 + f64::from(p.x) * self.scale.0,
+            self.origin.1 + f64::from(p.y) * self.scale.1,
+        )
+    }
+
+    fn line(&self, from: Point, to: Point, color: Color) -> CanvasLine {
+        let ((x1, y1), (x2, y2)) = (self.point(from), self.point(to));
+        CanvasLine::new(x1, y1, x2, y2, color)
+    }
+
+    fn draw_guides(&self, ctx: &mut Context<'_>) {
+        let o = &self.outline;
+        let [x_min, y_min, x_max, y_max] = o.em_bounds();
+        let horizontal = [
+            (GuideKind::Ascender, Some(o.ascender)),
+            (GuideKind::CapHeight, o.cap_height),
+            (GuideKind::XHeight, o.x_height),
+            (GuideKind::Descender, Some(o.descender)),
+            (GuideKind::Baseline, Some(0)),
+        ];
+        for (kind, y) in horizontal {
+            if let Some(y) = y.map(f32::from) {
+                let (from, to) = (Point { x: x_min, y }, Point { x: x_max, y });
+                ctx.draw(&self.line(from, to, guide_color(kind)));
+            }
+        }
+        for (kind, x) in [(GuideKind::Origin, 0), (GuideKind::Advance, o.advance)] {
+            let x = f32::from(x);
+            let (from, to) = (Point { x, y: y_min }, Point { x, y: y_max });
+            ctx.draw(&self.line(from, to, guide_color(kind)));
+        }
+    }
+
+    /// Draw guides, contours and handles, then marks of points over them.
+    pub fn paint(&self, ctx: &mut Context<'_>) {
+        if self.em_box {
+            self.draw_guides(ctx);
+        }
+
+        for contour in &self.outline.contours {
+            for (from, segment) in contour.segments() {
+                match *segment {
+                    Segment::Line(_) => {}
+                    Segment::Quad(c, p) => {
+                        ctx.draw(&self.line(from, c, Color::DarkGray));
+                        ctx.draw(&self.line(c, p, Color::DarkGray));
+                    }
+                    Segment::Cubic(c1, c2, p) => {
+                        ctx.draw(&self.line(from, c1, Color::DarkGray));
+                        ctx.draw(&self.line(c2, p, Color::DarkGray));
+                    }
+                }
+                let mut last = from;
+                for p in segment.flatten(from) {
+                    ctx.draw(&self.line(last, p, Color::Reset));
+                    last = p;
+                }
+            }
+        }
+
+        let mark = |ctx: &mut Context<'_>, p: Point, c: char, color: Color| {
+            let (x, y) = self.point(p);
+            ctx.print(
+                x,
+                y,
+                Span::styled(c.to_string(), Style::default().fg(color)),
+            );
+        };
+        for contour in &self.outline.contours {
+            for segment in &contour.segments {
+                for c in segment.controls() {
+                    mark(ctx, c, '○', Color::Yellow);
+                }
+                mark(ctx, segment.end(), '●', Color::Blue);
+            }
+            mark(ctx, contour.start, '◆', Color::Red);
+            if let Some((p, d)) = contour.direction() {
+                mark(ctx, p, arrow(d.x, d.y), Color::Red);
+            }
+        }
+    }
+}
@@ -16,14 +16,18 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{io::Result as IoResult, ops::Deref, sync::mpsc, thread, time::Duration};
+use std::{io::Result as IoResult, ops::Deref, sync::mpsc, thread};
 
-use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind, MouseEvent};
 
 #[derive(Copy, Clone)]
 pub enum TerminalEvent {
-    Tick,
     Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// Terminal size changed, ratatui reads the new size when drawing
+    Resize,
+    FocusGained,
+    FocusLost,
 }
 
 pub struct TerminalEventStream {
@@ -31,11 +35,11 @@ pub struct TerminalEventStream {
 }
 
 impl TerminalEventStream {
-    pub fn new(tick_interval: Duration) -> Self {
+    pub fn new() -> Self {
         let (tx, rx) = mpsc::channel();
 
-        let keyboard_tx = tx;
-        thread::spawn(move || keyboard_event_generator(tick_interval, keyboard_tx));
+        let terminal_tx = tx;
+        thread::spawn(move || terminal_event_generator(terminal_tx));
 
         Self { rx }
     }
@@ -49,30 +53,20 @@ impl Deref for TerminalEventStream {
     }
 }
 
-fn keyboard_event_generator(tick_interval: Duration, tx: mpsc::Sender<IoResult<TerminalEvent>>) {
+/// Wait for terminal events and send them, until receiver is dropped.
+fn terminal_event_generator(tx: mpsc::Sender<IoResult<TerminalEvent>>) {
     loop {
-        match event::poll(tick_interval) {
-            Ok(true) => {
-                let ev = event::read().expect("read terminal event should not fail");
-                if let Event::Key(key) = ev {
-                    #[allow(clippy::collapsible_if)]
-                    if key.kind != KeyEventKind::Release {
-                        if tx.send(Ok(TerminalEvent::Key(key))).is_err() {
-                            break;
-                        }
-                    }
-                }
-            }
-            Ok(false) => {
-                if tx.send(Ok(TerminalEvent::Tick)).is_err() {
-                    break;
-                }
-            }
-            Err(kind) => {
-                if tx.send(Err(kind)).is_err() {
-                    break;
-                }
-            }
+        let ev = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => Ok(TerminalEvent::Key(key)),
+            Ok(Event::Mouse(mouse)) => Ok(TerminalEvent::Mouse(mouse)),
+            Ok(Event::Resize(..)) => Ok(TerminalEvent::Resize),
+            Ok(Event::FocusGained) => Ok(TerminalEvent::FocusGained),
+            Ok(Event::FocusLost) => Ok(TerminalEvent::FocusLost),
+            Ok(_) => continue,
+            Err(kind) => Err(kind),
+        };
+        if tx.send(ev).is_err() {
+            break;
         }
     }
 }
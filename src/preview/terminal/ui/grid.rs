@@ -0,0 +1,441 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use ratatui::{
+    Frame,
+    crossterm::event::{Event, KeyCode as CtKeyCode, KeyEvent},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph},
+};
+use tui_input::backend::crossterm::EventHandler;
+
+use super::draw_input;
+use crate::{
+    loader::{self, AxisValue, FaceInfo, GlyphMap},
+    one_char::OneChar,
+    preview::terminal::{
+        print::braille,
+        render::{MonoRender, Render, RenderType},
+    },
+    rasterizer::{self, Options, Rasterizer, Size},
+};
+
+/// Columns of a grid cell, includes a space between cells
+const CELL_WIDTH: usize = 8;
+/// Rows of a grid cell, glyph and its label
+const CELL_HEIGHT: usize = 4;
+/// Braille rows of glyph in a cell
+const GLYPH_ROWS: usize = CELL_HEIGHT - 1;
+
+/// What grid cells are.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum GridMode {
+    /// Encoded chars, in code point order
+    Chars,
+    /// All glyphs, in glyph id order
+    Glyphs,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Item {
+    c: Option<char>,
+    gid: u16,
+}
+
+pub enum GridEvent {
+    Continue,
+    ReDraw,
+    Close,
+    /// Use the char as query char
+    Select(OneChar),
+}
+
+/// A browser of all chars or glyphs of one face, in a grid of small braille renders.
+pub struct GlyphGrid {
+    face: FaceInfo,
+    coordinates: Vec<AxisValue>,
+    hscale: f32,
+    map: GlyphMap,
+    mode: GridMode,
+    /// Items match the search keyword
+    items: Vec<Item>,
+    selected: usize,
+    search: tui_input::Input,
+    searching: bool,
+    /// First visible row
+    scroll: Cell<usize>,
+    /// Visible (columns, rows) of last frame, for paging
+    size: Cell<(usize, usize)>,
+    /// Braille lines of each glyph
+    cache: RefCell<HashMap<u16, Vec<String>>>,
+}
+
+impl GlyphGrid {
+    /// `aspect` is cell height divided by width.
+    pub fn new(
+        face: &FaceInfo, coordinates: Vec<AxisValue>, aspect: f32,
+    ) -> Result<Self, loader::Error> {
+        let map = face.glyph_map()?;
+        let mut grid = Self {
+            face: face.clone(),
+            coordinates,
+            hscale: RenderType::Mono.hscale(aspect),
+            map,
+            mode: GridMode::Chars,
+            items: vec![],
+            selected: 0,
+            search: tui_input::Input::default(),
+            searching: false,
+            scroll: Cell::new(0),
+            size: Cell::new((1, 1)),
+            cache: RefCell::default(),
+        };
+        grid.update_items();
+        // Start at the glyph of query char
+        if let Some(i) = grid.items.iter().position(|item| item.gid == face.gid) {
+            grid.selected = i;
+        }
+        Ok(grid)
+    }
+
+    fn all_items(&self) -> Vec<Item> {
+        match self.mode {
+            GridMode::Chars => self
+                .map
+                .chars
+                .iter()
+                .map(|&(c, gid)| Item { c: Some(c), gid })
+                .collect(),
+            GridMode::Glyphs => (0..self.map.num_glyphs() as u16)
+                .map(|gid| Item {
+                    c: self.map.char_of(gid),
+                    gid,
+                })
+                .collect(),
+        }
+    }
+
+    /// Single char keyword matches the char only, others match code point, glyph and block
+    /// names.
+    fn matches(&self, item: &Item, keyword: &str) -> bool {
+        let mut chars = keyword.chars();
+        if let (Some(k), None) = (chars.next(), chars.next()) {
+            return item.c == Some(k);
+        }
+
+        let mut text = format!("#{}", item.gid);
+        if let Some(name) = self.map.name_of(item.gid) {
+            text.push(' ');
+            text.push_str(name);
+        }
+        if let Some(c) = item.c.map(OneChar) {
+            text.push_str(&format!(" U+{:04X}", u32::from(c.0)));
+            if let Some(block) = c.block() {
+                text.push(' ');
+                text.push_str(block.name);
+            }
+        }
+        text.to_lowercase().contains(&keyword.to_lowercase())
+    }
+
+    /// Apply mode and search keyword, keep selected glyph if it's still there.
+    fn update_items(&mut self) {
+        let selected = self.items.get(self.selected).map(|item| item.gid);
+        let keyword = self.search.value().trim();
+        self.items = self
+            .all_items()
+            .into_iter()
+            .filter(|item| keyword.is_empty() || self.matches(item, keyword))
+            .collect();
+        self.selected = selected
+            .and_then(|gid| self.items.iter().position(|item| item.gid == gid))
+            .unwrap_or(0);
+    }
+
+    fn render_glyph(r: &Rasterizer<'_>, gid: u16) -> Vec<String> {
+        r.rasterize(gid)
+            .map(|bitmap| braille(&MonoRender::default().render(&bitmap)))
+            .unwrap_or_default()
+    }
+
+    /// Render glyphs which are not in cache, all in one go to avoid loading font many times.
+    fn prepare(&self, items: &[Item]) {
+        let mut cache = self.cache.borrow_mut();
+        let missing: Vec<_> = items
+            .iter()
+            .map(|item| item.gid)
+            .filter(|gid| !cache.contains_key(gid))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let options = Options {
+            hscale: self.hscale,
+            em_box: true,
+            ..Options::new(Size::Height((GLYPH_ROWS * 4) as u32))
+        };
+        let _ = rasterizer::with_face(&self.face, &self.coordinates, options, |r| {
+            for gid in missing {
+                cache.insert(gid, Self::render_glyph(r, gid));
+            }
+        });
+    }
+
+    /// Label of a cell, hex code point or glyph id.
+    fn label(&self, item: &Item) -> String {
+        match (self.mode, item.c) {
+            (GridMode::Chars, Some(c)) => format!("{:04X}", u32::from(c)),
+            _ => format!("#{}", item.gid),
+        }
+    }
+
+    /// Make selected item visible, returns first visible row.
+    fn scroll_to_selected(&self, rows: usize) -> usize {
+        let (columns, _) = self.size.get();
+        let row = self.selected / columns;
+        let scroll = self
+            .scroll
+            .get()
+            .min(row)
+            .max((row + 1).saturating_sub(rows));
+        self.scroll.set(scroll);
+        scroll
+    }
+
+    fn draw_cells(&self, area: Rect, f: &mut Frame<'_>) {
+        let columns = (usize::from(area.width) / CELL_WIDTH).max(1);
+        let rows = (usize::from(area.height) / CELL_HEIGHT).max(1);
+        self.size.set((columns, rows));
+
+        let first = (self.scroll_to_selected(rows) * columns).min(self.items.len());
+        let last = (first + columns * rows).min(self.items.len());
+        let visible = &self.items[first..last];
+        self.prepare(visible);
+        let cache = self.cache.borrow();
+
+        let mut lines = vec![];
+        for (row, items) in visible.chunks(columns).enumerate() {
+            for line in 0..CELL_HEIGHT {
+                let spans: Vec<_> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(col, item)| {
+                        let selected = first + row * columns + col == self.selected;
+                        let (text, style) = if line < GLYPH_ROWS {
+                            let glyph = cache.get(&item.gid).and_then(|lines| lines.get(line));
+                            let style = if selected {
+                                Style::default().fg(Color::LightBlue)
+                            } else {
+                                Style::default()
+                            };
+                            (glyph.cloned().unwrap_or_default(), style)
+                        } else if selected {
+                            (
+                                self.label(item),
+                                Style::default().add_modifier(Modifier::REVERSED),
+                            )
+                        } else {
+                            (self.label(item), Style::default().fg(Color::DarkGray))
+                        };
+                        Span::styled(format!("{} ", fit(&text, CELL_WIDTH - 1)), style)
+                    })
+                    .collect();
+                lines.push(Line::from(spans));
+            }
+        }
+
+        f.render_widget(Paragraph::new(Text::from(lines)), area);
+    }
+}
+
+impl GlyphGrid {
+    fn title(&self) -> String {
+        let (kind, total) = match self.mode {
+            GridMode::Chars => ("Chars", self.map.chars.len()),
+            GridMode::Glyphs => ("Glyphs", self.map.num_glyphs()),
+        };
+        format!("{kind} of {} {}/{total}", self.face.name, self.items.len())
+    }
+
+    fn draw_search(&self, area: Rect, f: &mut Frame<'_>) {
+        draw_input(area, f, &self.search, self.searching, "Search");
+    }
+
+    fn draw_info(&self, area: Rect, f: &mut Frame<'_>) {
+        let green = Style::default().fg(Color::Green);
+        let blue = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::BOLD);
+
+        let mut info = vec![];
+        if let Some(item) = self.items.get(self.selected) {
+            if let Some(c) = item.c {
+                info.extend([
+                    Span::styled("Char", green),
+                    Span::raw(": "),
+                    Span::styled(OneChar(c).summary(), blue),
+                    Span::raw(" "),
+                ]);
+            }
+            let name = self.map.name_of(item.gid).unwrap_or("-");
+            info.extend([
+                Span::styled("Glyph", green),
+                Span::raw(": "),
+                Span::styled(format!("#{} {name}", item.gid), blue),
+            ]);
+        }
+
+        let help = if self.searching {
+            "[Esc/Enter]: Exit Search"
+        } else {
+            "[Arrows/PgUp/PgDn]: Move  [{/}]: Block  [Tab]: Chars/Glyphs  [S/Slash]: Search  \
+             [Enter]: Use Char  [Esc/G]: Close"
+        };
+
+        let paragraph = Paragraph::new(Text::from(vec![
+            Line::from(info),
+            Line::styled(help, Style::default().fg(Color::Cyan)),
+        ]))
+        .block(Block::default().title("Info").borders(Borders::ALL));
+        f.render_widget(paragraph, area);
+    }
+
+    pub fn draw(&self, area: Rect, f: &mut Frame<'_>) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(3),
+                Constraint::Length(4),
+            ])
+            .split(area);
+
+        let block = Block::default().title(self.title()).borders(Borders::ALL);
+        self.draw_cells(block.inner(rows[0]), f);
+        f.render_widget(block, rows[0]);
+        self.draw_search(rows[1], f);
+        self.draw_info(rows[2], f);
+    }
+
+    /// Block of item at `i`, by its first code point.
+    fn block_key(&self, i: usize) -> Option<u32> {
+        let c = self.items.get(i)?.c?;
+        Some(OneChar(c).block()?.first)
+    }
+
+    /// First item which is not in the block of selected item.
+    fn next_block(&self) -> usize {
+        let key = self.block_key(self.selected);
+        (self.selected + 1..self.items.len())
+            .find(|&i| self.block_key(i) != key)
+            .unwrap_or(self.selected)
+    }
+
+    /// First item of current block, or previous block if it's already the first one.
+    fn prev_block(&self) -> usize {
+        let mut i = self.selected;
+        if i > 0 && self.block_key(i - 1) != self.block_key(i) {
+            i -= 1;
+        }
+        let key = self.block_key(i);
+        while i > 0 && self.block_key(i - 1) == key {
+            i -= 1;
+        }
+        i
+    }
+
+    fn on_key_search(&mut self, key: KeyEvent) -> GridEvent {
+        if matches!(key.code, CtKeyCode::Enter | CtKeyCode::Esc) {
+            self.searching = false;
+            return GridEvent::ReDraw;
+        }
+
+        match self.search.handle_event(&Event::Key(key)) {
+            Some(change) => {
+                if change.value {
+                    self.update_items();
+                }
+                GridEvent::ReDraw
+            }
+            None => GridEvent::Continue,
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> GridEvent {
+        if self.searching {
+            return self.on_key_search(key);
+        }
+
+        let (columns, rows) = self.size.get();
+        let selected = self.selected;
+        let target = match key.code {
+            CtKeyCode::Esc | CtKeyCode::Char('q') | CtKeyCode::Char('g') => {
+                return GridEvent::Close;
+            }
+            CtKeyCode::Enter => {
+                return match self.items.get(selected).and_then(|item| item.c) {
+                    Some(c) => GridEvent::Select(OneChar(c)),
+                    None => GridEvent::Continue,
+                };
+            }
+            CtKeyCode::Tab => {
+                self.mode = match self.mode {
+                    GridMode::Chars => GridMode::Glyphs,
+                    GridMode::Glyphs => GridMode::Chars,
+                };
+                self.update_items();
+                return GridEvent::ReDraw;
+            }
+            CtKeyCode::Char('s') | CtKeyCode::Char('/') => {
+                self.searching = true;
+                return GridEvent::ReDraw;
+            }
+            CtKeyCode::Left | CtKeyCode::Char('h') => selected.saturating_sub(1),
+            CtKeyCode::Right | CtKeyCode::Char('l') => selected + 1,
+            CtKeyCode::Up | CtKeyCode::Char('k') => selected.saturating_sub(columns),
+            CtKeyCode::Down | CtKeyCode::Char('j') => selected + columns,
+            CtKeyCode::PageUp => selected.saturating_sub(columns * rows),
+            CtKeyCode::PageDown => selected + columns * rows,
+            CtKeyCode::Home => 0,
+            CtKeyCode::End => usize::MAX,
+            CtKeyCode::Char('{') => self.prev_block(),
+            CtKeyCode::Char('}') => self.next_block(),
+            _ => return GridEvent::Continue,
+        };
+        self.selected = target.min(self.items.len().saturating_sub(1));
+        GridEvent::ReDraw
+    }
+}
+
+/// Center `text` in `width` columns, cut both sides if it's wider.
+fn fit(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len > width {
+        text.chars().skip((len - width) / 2).take(width).collect()
+    } else {
+        format!("{text:^width$}")
+    }
+}
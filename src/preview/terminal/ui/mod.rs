@@ -18,77 +18,231 @@
 
 mod cache;
 mod event;
+mod grid;
 mod state;
+mod variation;
 
 use std::{
-    io::{Result as IoResult, Stdout},
-    time::Duration,
+    cell::{Cell, RefCell},
+    io::{Result as IoResult, Stdout, Write},
+    rc::Rc,
 };
 
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
+    buffer::Buffer,
     crossterm::{
-        event::{Event, KeyCode as CtKeyCode, KeyEvent, KeyModifiers as CtKM},
-        execute,
+        cursor::MoveTo,
+        event::{
+            DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event,
+            KeyCode as CtKeyCode, KeyEvent, KeyModifiers as CtKM, MouseButton, MouseEvent,
+            MouseEventKind,
+        },
+        execute, queue,
         terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
     },
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, Paragraph, canvas::Canvas},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, canvas::Canvas},
 };
 use tui_input::backend::crossterm::EventHandler;
 
 use self::{
-    cache::{GlyphCache, GlyphCanvasShape},
+    cache::{
+        CachedGlyph, GlyphCache, GlyphCanvasShape, GlyphOutlineShape, OVERLAY_COLORS, RenderType,
+        guide_color,
+    },
     event::{TerminalEvent, TerminalEventStream},
-    state::State,
+    grid::{GlyphGrid, GridEvent},
+    state::{State, View},
+};
+use crate::{
+    family::FilteredFamilies,
+    loader::{AxisValue, or_dash},
+    one_char::OneChar,
+    preview::terminal::graphics::GraphicsMode,
+    rasterizer::{GuideKind, Overlap},
 };
-use crate::family::FilteredFamilies;
+
+/// 10 rows of glyph metrics and borders
+const METRICS_PANEL_HEIGHT: u16 = 12;
+
+/// Metrics panel is hidden if font list would be shorter than this, borders included
+const MIN_LIST_HEIGHT: u16 = 8;
+
+/// Keys and what they do, shown in help popup
+const KEY_HELP: [(&str, &str); 22] = [
+    ("Up/Down, J/K", "Select font"),
+    ("Left/Right, H/L", "Change render"),
+    ("S, /", "Edit filter"),
+    ("C", "Edit char"),
+    ("N/P", "Next/previous code point"),
+    ("Shift+N/P", "Next/previous assigned char"),
+    ("}/{", "Next/previous Unicode block"),
+    ("E", "Toggle em box"),
+    ("G", "Glyph grid of selected font"),
+    ("M", "Mark selected font to compare"),
+    ("V", "Compare marked fonts"),
+    ("R", "Pin selected font as reference"),
+    ("O", "Overlay selected font on reference"),
+    ("W", "Waterfall of sizes"),
+    ("D", "Change dither (Mono)"),
+    (",/.", "Adjust threshold (Mono)"),
+    ("[/]", "Select variation axis"),
+    ("-/=", "Adjust variation axis"),
+    ("0", "Reset variation axes"),
+    ("Wheel", "Scroll list or zoom preview"),
+    ("?", "Show or hide this help"),
+    ("Q", "Quit"),
+];
+
+/// Braille threshold change of one key press
+const THRESHOLD_STEP: i16 = 8;
+
+/// Pixel heights of glyph in waterfall view
+const WATERFALL_SIZES: [u32; 12] = [8, 10, 12, 14, 16, 20, 24, 32, 48, 64, 96, 128];
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 enum OnEventResult {
     ReDraw,
+    /// Redraw the whole screen and images on it, which terminal may have lost
+    Refresh,
     Continue,
     Exit,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum WhichInput {
     Search,
+    Char,
 }
 
-pub struct UI<'a> {
-    idle_redraw: u8,
+/// Image glyph in preview area or compare cell, which is written to terminal after each frame.
+#[derive(Clone)]
+struct PreviewImage {
+    area: Rect,
+    glyph: Rc<Result<CachedGlyph, &'static str>>,
+}
+
+impl PartialEq for PreviewImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.area == other.area && Rc::ptr_eq(&self.glyph, &other.glyph)
+    }
+}
+
+/// Screen areas of last frame, to find what mouse events point to.
+#[derive(Debug, Copy, Clone, Default)]
+struct Areas {
+    list: Rect,
+    char_box: Rect,
+    filter: Rect,
+    preview: Rect,
+    render_label: Rect,
+}
+
+/// Draw a one line input box, with cursor at the input position if `editing`.
+fn draw_input<'t>(
+    area: Rect, f: &mut Frame<'_>, input: &tui_input::Input, editing: bool,
+    title: impl Into<Line<'t>>,
+) {
+    let scroll = input.visual_scroll(usize::from(area.width).saturating_sub(3));
+
+    let style = if editing {
+        let x = input.visual_cursor().max(scroll) - scroll;
+        f.set_cursor_position((area.x + 1 + x as u16, area.y + 1));
+
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+
+    let paragraph = Paragraph::new(input.value())
+        .scroll((0, if editing { scroll as u16 } else { 0 }))
+        .style(style)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(paragraph, area);
+}
+
+pub struct UI {
     filter_input: tui_input::Input,
+    char_input: tui_input::Input,
+    /// Why the input char can't be used
+    char_error: Option<String>,
     editing: Option<WhichInput>,
-    state: State<'a>,
+    /// Key help popup is shown over main screen
+    help: bool,
+    state: State,
+    /// Glyph grid of selected face, shown instead of main screen
+    grid: Option<GlyphGrid>,
+    graphics: GraphicsMode,
+    /// Images to show after current frame
+    images: RefCell<Vec<PreviewImage>>,
+    /// Images on screen now
+    shown_images: Vec<PreviewImage>,
+    /// Cells of last frame, written again to cover removed images
+    frame: Buffer,
+    areas: Cell<Areas>,
 }
 
-impl<'a> UI<'a> {
-    pub fn new(filtered: FilteredFamilies<'a>) -> Option<Self> {
+impl UI {
+    pub fn new(
+        c: OneChar, filtered: FilteredFamilies, user_values: Vec<AxisValue>,
+        graphics: GraphicsMode, cell_aspect: Option<f32>,
+    ) -> Option<Self> {
         if !filtered.is_empty() {
             Some(Self {
-                idle_redraw: 0,
                 filter_input: tui_input::Input::new(filtered.keyword().to_string()),
+                char_input: tui_input::Input::new(c.0.to_string()),
+                char_error: None,
                 editing: None,
-                state: State::new(filtered),
+                help: false,
+                state: State::new(c, filtered, user_values, cell_aspect),
+                grid: None,
+                graphics,
+                images: RefCell::default(),
+                shown_images: Vec::new(),
+                frame: Buffer::default(),
+                areas: Cell::default(),
             })
         } else {
             None
         }
     }
 
+    fn update_areas(&self, update: impl FnOnce(&mut Areas)) {
+        let mut areas = self.areas.get();
+        update(&mut areas);
+        self.areas.set(areas);
+    }
+
     fn draw_list(&self, area: Rect, f: &mut Frame<'_>) {
-        let families = self.state.font_face_names();
+        let families = self.state.font_faces().map(|face| {
+            let name = face.name.as_ref();
+            let item = ListItem::new(name);
+            let mut style = Style::default();
+            if self.state.is_marked(face) {
+                style = style.fg(Color::Yellow);
+            }
+            if self.state.is_reference(face) {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            item.style(style)
+        });
         let index = self.state.index();
-        let title = format!(
+        let mut title = format!(
             "Fonts {}/{}",
             index.map(|x| x + 1).unwrap_or_default(),
             self.state.len()
         );
+        let marked = self.state.marked_count();
+        if marked > 0 {
+            title.push_str(&format!(" ({marked} marked)"));
+        }
 
-        let list = List::new(families.map(ListItem::new).collect::<Vec<_>>())
+        let list = List::new(families.collect::<Vec<_>>())
             .block(
                 Block::default()
                     .title(Span::raw(title))
@@ -103,33 +257,174 @@ impl<'a> UI<'a> {
         f.render_stateful_widget(list, area, &mut self.state.mut_list_state())
     }
 
+    fn draw_input<'t>(
+        &self, area: Rect, f: &mut Frame<'_>, which: WhichInput, title: impl Into<Line<'t>>,
+    ) {
+        let editing = self.editing == Some(which);
+        let idle;
+        let input = match which {
+            WhichInput::Search => &self.filter_input,
+            // Show code point of current char when not editing
+            WhichInput::Char if !editing => {
+                idle = tui_input::Input::new(self.state.char().label());
+                &idle
+            }
+            WhichInput::Char => &self.char_input,
+        };
+        draw_input(area, f, input, editing, title);
+    }
+
     fn draw_filter_input(&self, area: Rect, f: &mut Frame<'_>) {
-        let searchbox = &self.filter_input;
-        let scroll = searchbox.visual_scroll(area.width as usize - 3);
+        self.draw_input(area, f, WhichInput::Search, "Filter");
+    }
 
-        let style = if let Some(WhichInput::Search) = self.editing {
-            let x = searchbox.visual_cursor().max(scroll) - scroll;
-            f.set_cursor_position((area.x + 1 + x as u16, area.y + 1));
+    fn draw_char_input(&self, area: Rect, f: &mut Frame<'_>) {
+        let title = match self.char_error {
+            Some(ref err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None => Line::raw("Char"),
+        };
+        self.draw_input(area, f, WhichInput::Char, title);
+    }
 
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default()
+    fn draw_variation(&self, area: Rect, f: &mut Frame<'_>) {
+        let variation = self.state.variation();
+        let selected = variation.selected();
+
+        // marker(2) + tag(4) + space + bar + space + value(7)
+        let bar_width = (area.width as usize).saturating_sub(2 + 15);
+
+        let lines: Vec<Line<'_>> = variation
+            .iter()
+            .enumerate()
+            .map(|(i, (axis, value))| {
+                let mut bar = String::new();
+                if bar_width >= 3 {
+                    let range = axis.max_value - axis.min_value;
+                    let ratio = if range > 0.0 {
+                        (value - axis.min_value) / range
+                    } else {
+                        0.0
+                    };
+                    let pos = (ratio * (bar_width - 1) as f32).round() as usize;
+                    bar = (0..bar_width)
+                        .map(|x| if x == pos { '●' } else { '─' })
+                        .collect();
+                }
+
+                let text = format!(
+                    "{}{} {} {:>7.1}",
+                    if i == selected { "> " } else { "  " },
+                    axis.tag,
+                    bar,
+                    value,
+                );
+
+                if i == selected {
+                    Line::styled(
+                        text,
+                        Style::default()
+                            .fg(Color::LightBlue)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Line::raw(text)
+                }
+            })
+            .collect();
+
+        let title = variation
+            .iter()
+            .nth(selected)
+            .map(|(axis, _)| {
+                format!(
+                    "{} {}~{}",
+                    axis.name.as_deref().unwrap_or("Variation"),
+                    axis.min_value,
+                    axis.max_value
+                )
+            })
+            .unwrap_or_default();
+
+        let paragraph = Paragraph::new(Text::from(lines))
+            .block(Block::default().title(title).borders(Borders::ALL));
+
+        f.render_widget(paragraph, area);
+    }
+
+    /// Preview block, with legend of guide lines in title when em box mode is on.
+    fn preview_block(&self, name: &'static str) -> Block<'static> {
+        let mut title = vec![Span::raw(name)];
+        // Compared faces always have em box to share the baseline
+        if self.state.em_box() || self.state.view() == View::Compare {
+            title.push(Span::raw(" "));
+            for kind in [
+                GuideKind::Ascender,
+                GuideKind::CapHeight,
+                GuideKind::XHeight,
+                GuideKind::Baseline,
+                GuideKind::Advance,
+            ] {
+                title.push(Span::raw(" "));
+                title.push(Span::styled(
+                    kind.name(),
+                    Style::default().fg(guide_color(kind)),
+                ));
+            }
+        }
+        Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+    }
+
+    fn draw_metrics(&self, area: Rect, f: &mut Frame<'_>) {
+        let rows: Vec<(&str, String)> = match self.state.glyph_metrics() {
+            Some(m) => vec![
+                ("ID", m.gid.to_string()),
+                ("Name", or_dash(m.name.as_deref())),
+                ("Adv W", or_dash(m.advance_width)),
+                ("Adv H", or_dash(m.advance_height)),
+                ("LSB", or_dash(m.left_side_bearing)),
+                ("TSB", or_dash(m.top_side_bearing)),
+                (
+                    "BBox",
+                    or_dash(m.bbox.map(|b| format!("{} {}", b.x_min, b.y_min))),
+                ),
+                (
+                    "",
+                    m.bbox
+                        .map_or_else(String::new, |b| format!("{} {}", b.x_max, b.y_max)),
+                ),
+                ("UPM", m.units_per_em.to_string()),
+                ("Format", m.format_names()),
+            ],
+            None => vec![],
         };
 
-        let input = Paragraph::new(searchbox.value())
-            .scroll((0, scroll as u16))
-            .style(style)
-            .block(Block::default().borders(Borders::ALL).title("Filter"));
+        let lines: Vec<_> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label:<7}"), Style::default().fg(Color::Green)),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+
+        let paragraph = Paragraph::new(Text::from(lines))
+            .block(Block::default().title("Glyph").borders(Borders::ALL));
 
-        f.render_widget(input, area);
+        f.render_widget(paragraph, area);
     }
 
-    fn draw_preview_canvas(&self, area: Rect, f: &mut Frame<'_>, shape: &GlyphCanvasShape) {
-        let (canvas_width, canvas_height) = self.state.get_canvas_size_by_pixel();
-        let canvas_width = f64::from(canvas_width);
-        let canvas_height = f64::from(canvas_height);
+    fn draw_preview_canvas(
+        &self, area: Rect, f: &mut Frame<'_>, block: Block<'_>, shape: &GlyphCanvasShape,
+    ) {
+        let inner = block.inner(area);
+        let (cols, rows) = RenderType::Mono.cell_size();
+        let canvas_width = f64::from(u32::from(inner.width) * cols);
+        let canvas_height = f64::from(u32::from(inner.height) * rows);
         let canvas = Canvas::default()
-            .block(Block::default().title("Preview").borders(Borders::ALL))
+            .block(block)
             .x_bounds([0.0, canvas_width])
             .y_bounds([0.0, canvas_height])
             .paint(|ctx| {
@@ -138,12 +433,26 @@ impl<'a> UI<'a> {
         f.render_widget(canvas, area);
     }
 
-    fn draw_preview_paragraph<'s, I>(&self, area: Rect, f: &mut Frame<'_>, paragraph: I)
-    where
-        I: IntoIterator<Item = &'s str>,
+    fn draw_preview_outline(
+        &self, area: Rect, f: &mut Frame<'_>, block: Block<'_>, shape: &GlyphOutlineShape,
+    ) {
+        let inner = block.inner(area);
+        let (cols, rows) = RenderType::Outline.cell_size();
+        let canvas = Canvas::default()
+            .block(block)
+            .x_bounds([0.0, f64::from(u32::from(inner.width) * cols)])
+            .y_bounds([0.0, f64::from(u32::from(inner.height) * rows)])
+            .paint(|ctx| shape.paint(ctx));
+        f.render_widget(canvas, area);
+    }
+
+    fn draw_preview_paragraph<'s, I>(
+        &self, area: Rect, f: &mut Frame<'_>, block: Block<'_>, paragraph: I,
+    ) where
+        I: IntoIterator<Item = Line<'s>>,
         I::IntoIter: ExactSizeIterator,
     {
-        let (_, height) = self.state.get_canvas_size_by_char();
+        let height = block.inner(area).height;
 
         let iter = paragraph.into_iter();
 
@@ -152,12 +461,10 @@ impl<'a> UI<'a> {
         let padding = (height as usize).saturating_sub(iter.len());
         let mut lines = vec![Line::from(""); padding / 2];
 
-        for line in iter {
-            lines.push(Line::from(line));
-        }
+        lines.extend(iter);
 
         let canvas = Paragraph::new(Text::from(lines))
-            .block(Block::default().title("Preview").borders(Borders::ALL))
+            .block(block)
             .alignment(Alignment::Center)
             .style(
                 Style::default()
@@ -167,24 +474,192 @@ impl<'a> UI<'a> {
         f.render_widget(canvas, area);
     }
 
+    /// Draw a rendered glyph in `block`, images are shown after the frame.
+    fn draw_glyph(
+        &self, area: Rect, f: &mut Frame<'_>, block: Block<'_>,
+        glyph: Rc<Result<CachedGlyph, &'static str>>,
+    ) {
+        match glyph.as_ref().as_ref().map(|glyph| &glyph.content) {
+            Ok(GlyphCache::Canvas(shape)) => self.draw_preview_canvas(area, f, block, shape),
+            Ok(GlyphCache::Outline(shape)) => self.draw_preview_outline(area, f, block, shape),
+            Ok(GlyphCache::Paragraph(s)) => {
+                self.draw_preview_paragraph(area, f, block, s.lines.iter().cloned())
+            }
+            Ok(GlyphCache::Image(_)) => {
+                let inner = block.inner(area);
+                self.draw_preview_paragraph(area, f, block, []);
+                self.images
+                    .borrow_mut()
+                    .push(PreviewImage { area: inner, glyph });
+            }
+            Err(s) => self.draw_preview_paragraph(area, f, block, [Line::from(*s)]),
+        }
+    }
+
     fn draw_preview(&self, area: Rect, f: &mut Frame<'_>) {
-        let result = self.state.render();
+        match self.state.view() {
+            View::Single => self.draw_single(area, f),
+            View::Compare => self.draw_compare(area, f),
+            View::Overlay => self.draw_overlay(area, f),
+            View::Waterfall => self.draw_waterfall(area, f),
+        }
+    }
 
-        if let Some(result) = result {
-            match result.as_ref().as_ref() {
-                Ok(GlyphCache::Canvas(shape)) => {
-                    self.draw_preview_canvas(area, f, shape);
-                }
-                Ok(GlyphCache::Paragraph(s)) => {
-                    self.draw_preview_paragraph(area, f, s.lines.iter().map(|s| s.as_str()))
-                }
-                Err(s) => {
-                    let paragraph: [&str; 1] = [s];
-                    self.draw_preview_paragraph(area, f, paragraph)
+    fn draw_single(&self, area: Rect, f: &mut Frame<'_>) {
+        if let Some(glyph) = self.state.render() {
+            self.draw_glyph(area, f, self.preview_block("Preview"), glyph);
+        } else if !self.state.has_fonts() {
+            self.draw_preview_paragraph(area, f, self.preview_block("Preview"), [Line::from(
+                "No font supports this character",
+            )])
+        } else {
+            self.draw_preview_paragraph(area, f, self.preview_block("Preview"), [])
+        }
+    }
+
+    /// Legend of overlay colors and similarity, at right of preview title.
+    fn overlay_legend(overlap: Option<Overlap>) -> Line<'static> {
+        let mut legend = vec![];
+        for (name, [r, g, b, _]) in ["Selected", "Reference", "Both"]
+            .into_iter()
+            .zip(OVERLAY_COLORS)
+        {
+            legend.push(Span::styled(
+                format!("■ {name}"),
+                Style::default().fg(Color::Rgb(r, g, b)),
+            ));
+            legend.push(Span::raw(" "));
+        }
+        if let Some(overlap) = overlap {
+            legend.push(Span::styled(
+                format!("Similarity {:.1}%", overlap.similarity() * 100.0),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+        Line::from(legend).right_aligned()
+    }
+
+    /// Selected face over the pinned reference face.
+    fn draw_overlay(&self, area: Rect, f: &mut Frame<'_>) {
+        let block = self.preview_block("Overlay");
+        let message = if self.state.reference().is_none() {
+            "Press R to pin selected font as reference"
+        } else {
+            "Reference font does not support this character"
+        };
+
+        match self.state.render_overlay() {
+            Some(glyph) => {
+                let overlap = glyph.as_ref().as_ref().ok().and_then(|glyph| glyph.overlap);
+                let block = block.title(Self::overlay_legend(overlap));
+                self.draw_glyph(area, f, block, glyph);
+            }
+            None if self.state.index().is_none() => self.draw_preview_paragraph(area, f, block, []),
+            None => self.draw_preview_paragraph(area, f, block, [Line::from(message)]),
+        }
+    }
+
+    /// Selected face at fixed sizes from small to large, as many as fit the area.
+    fn draw_waterfall(&self, area: Rect, f: &mut Frame<'_>) {
+        let block = self.preview_block("Waterfall");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let (mut x, mut y, mut row_height) = (inner.x, inner.y, 0);
+        for size in WATERFALL_SIZES {
+            let title = format!("{size}px");
+            let (width, height) = self.state.waterfall_cell(size);
+            // Borders are outside of glyph
+            let width = (width as u16).max(title.len() as u16) + 2;
+            let height = height as u16 + 2;
+
+            if x + width > inner.right() {
+                (x, y, row_height) = (inner.x, y + row_height, 0);
+            }
+            if width > inner.width || y + height > inner.bottom() {
+                break;
+            }
+
+            let cell = Rect::new(x, y, width, height);
+            let block = Block::default().title(title).borders(Borders::ALL);
+            let inner = block.inner(cell);
+            let Some(glyph) =
+                self.state
+                    .render_waterfall(size, u32::from(inner.width), u32::from(inner.height))
+            else {
+                return;
+            };
+            self.draw_glyph(cell, f, block, glyph);
+
+            x += width;
+            row_height = row_height.max(height);
+        }
+    }
+
+    /// Columns of compare grid which give cells the largest glyph size.
+    fn compare_columns(area: Rect, count: usize) -> usize {
+        (1..=count.max(1))
+            .max_by_key(|&cols| {
+                let rows = count.div_ceil(cols);
+                let (width, height) = (area.width as usize / cols, area.height as usize / rows);
+                // Glyph is about as wide as twice its height in chars
+                (width / 2).min(height)
+            })
+            .unwrap_or(1)
+    }
+
+    /// Marked faces side by side, rendered at the same scale and baseline.
+    fn draw_compare(&self, area: Rect, f: &mut Frame<'_>) {
+        let block = self.preview_block("Compare");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let faces = self.state.compared_faces();
+        if faces.is_empty() {
+            let hint =
+                Paragraph::new("Press M to mark fonts to compare").alignment(Alignment::Center);
+            f.render_widget(hint, inner);
+            return;
+        }
+
+        let shared_em = self
+            .state
+            .shared_em(faces.iter().filter_map(|(_, index)| *index));
+        let selected = self.state.current_index();
+
+        let cols = Self::compare_columns(inner, faces.len());
+        let rows =
+            Layout::vertical(vec![Constraint::Fill(1); faces.len().div_ceil(cols)]).split(inner);
+        let cells = rows.iter().flat_map(|row| {
+            Layout::horizontal(vec![Constraint::Fill(1); cols])
+                .split(*row)
+                .to_vec()
+        });
+
+        for ((name, index), cell) in faces.into_iter().zip(cells) {
+            let style = if index.is_some() && index == selected {
+                Style::default()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let block = Block::default()
+                .title(Span::styled(name, style))
+                .borders(Borders::ALL);
+            match index {
+                Some(index) => {
+                    let inner = block.inner(cell);
+                    let glyph = self.state.render_compare(
+                        index,
+                        u32::from(inner.width),
+                        u32::from(inner.height),
+                        shared_em,
+                    );
+                    self.draw_glyph(cell, f, block, glyph);
                 }
+                None => self.draw_preview_paragraph(cell, f, block, [Line::from("Not supported")]),
             }
-        } else {
-            self.draw_preview_paragraph(area, f, [])
         }
     }
 
@@ -207,15 +682,105 @@ impl<'a> UI<'a> {
     }
 
     fn draw_status_bar_info(&self, area: Rect, f: &mut Frame<'_>) {
+        let mut mode_texts = vec![];
+        if self.state.get_render_type() == &RenderType::Mono {
+            mode_texts.extend([
+                Span::styled("Dither", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    self.state.dither().name(),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::styled("Threshold", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    self.state.threshold().to_string(),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+            ]);
+        }
+        if let Some((contours, on, off)) = self.state.outline_summary() {
+            mode_texts.extend([
+                Span::styled("Contours", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    contours.to_string(),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::styled("Points", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    format!("{on} on, {off} off"),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+            ]);
+        }
+        if self.state.view() == View::Single && self.state.zoom() != 1.0 {
+            mode_texts.extend([
+                Span::styled("Zoom", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    format!("{:.0}%", self.state.zoom() * 100.0),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+            ]);
+        }
+        let render_label = [
+            Span::styled("Render", Style::default().fg(Color::Green)),
+            Span::raw(": "),
+            Span::styled(
+                match (self.state.get_render_type(), self.state.protocol()) {
+                    (RenderType::Image, Some(protocol)) => format!("Image({protocol:?})"),
+                    (rt, _) => format!("{rt:?}"),
+                },
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        let label_width = render_label.iter().map(Span::width).sum::<usize>() as u16;
+        mode_texts.extend(render_label);
+        let mode_texts = Line::from(mode_texts);
+
+        // Mode part gets all width it needs, with right border
         let cols = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(mode_texts.width() as u16 + 1),
+            ])
             .split(area);
 
         let name = cols[0];
         let mode = cols[1];
 
-        let texts = vec![
+        // Label is at right end of the line under top border, left to the right border
+        let right = mode.right().saturating_sub(1);
+        self.update_areas(|areas| {
+            areas.render_label = Rect::new(
+                right.saturating_sub(label_width),
+                mode.y + 1,
+                label_width,
+                1,
+            )
+        });
+
+        let mut texts = vec![
             Span::styled("Font Face", Style::default().fg(Color::Green)),
             Span::raw(": "),
             Span::styled(
@@ -225,6 +790,44 @@ impl<'a> UI<'a> {
                     .add_modifier(Modifier::BOLD),
             ),
         ];
+
+        texts.extend([
+            Span::raw(" "),
+            Span::styled("Char", Style::default().fg(Color::Green)),
+            Span::raw(": "),
+            Span::styled(
+                self.state.char().summary(),
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]);
+        if let Some(reference) = self.state.reference() {
+            texts.extend([
+                Span::raw(" "),
+                Span::styled("Reference", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    reference,
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]);
+        }
+        if let Some(strike) = self.state.current_strike() {
+            texts.extend([
+                Span::raw(" "),
+                Span::styled("Strike", Style::default().fg(Color::Green)),
+                Span::raw(": "),
+                Span::styled(
+                    format!("{strike}px"),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]);
+        }
         f.render_widget(
             Paragraph::new(Line::from(texts))
                 .block(
@@ -236,81 +839,85 @@ impl<'a> UI<'a> {
             name,
         );
 
-        let texts = vec![
-            Span::styled("Render", Style::default().fg(Color::Green)),
-            Span::raw(": "),
-            Span::styled(
-                format!("{:?}", self.state.get_render_type()),
-                Style::default()
-                    .fg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ];
         f.render_widget(
-            Paragraph::new(Line::from(texts))
+            Paragraph::new(mode_texts)
                 .block(Block::default().borders(Borders::TOP | Borders::RIGHT))
                 .alignment(Alignment::Right),
             mode,
         );
     }
 
+    /// Most used keys, others are in help popup.
     fn draw_status_bar_help(&self, area: Rect, f: &mut Frame<'_>) {
-        let cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-            ])
-            .split(area);
-
-        let filter_help = if self.editing.is_none() {
-            Self::generate_help_text("[S/Slash]", "Filter Box")
-        } else {
-            Self::generate_help_text("[ESC/Enter]", "Exit Edit")
-        };
-        let list_help = Self::generate_help_text("[Up/Down]", "Select Font");
-        let mode_help = Self::generate_help_text(
-            "[Left/Right]",
-            if self.editing.is_none() {
-                "Change Render"
-            } else {
-                "Move Cursor"
-            },
-        );
-        let quit_help = if self.editing.is_none() {
-            Self::generate_help_text("[Q]", "Quit")
+        let helps = if self.editing.is_none() {
+            [
+                ("[?]", "Help"),
+                ("[Q]", "Quit"),
+                ("[Up/Down]", "Select Font"),
+                ("[Left/Right]", "Change Render"),
+                ("[S/C]", "Filter/Char"),
+            ]
+            .as_slice()
         } else {
-            filter_help.clone()
+            [
+                ("[ESC/Enter]", "Exit Edit"),
+                ("[Up/Down]", "Select Font"),
+                ("[Left/Right]", "Move Cursor"),
+            ]
+            .as_slice()
         };
 
-        f.render_widget(
-            Paragraph::new(Line::from(filter_help))
-                .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT))
-                .alignment(Alignment::Left),
-            cols[0],
-        );
+        let mut texts = vec![];
+        for (i, (key, help)) in helps.iter().enumerate() {
+            if i > 0 {
+                texts.push(Span::raw("  "));
+            }
+            texts.extend(Self::generate_help_text(key, help));
+        }
 
         f.render_widget(
-            Paragraph::new(Line::from(list_help))
-                .block(Block::default().borders(Borders::BOTTOM))
-                .alignment(Alignment::Center),
-            cols[1],
+            Paragraph::new(Line::from(texts))
+                .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)),
+            area,
         );
+    }
 
-        f.render_widget(
-            Paragraph::new(Line::from(mode_help))
-                .block(Block::default().borders(Borders::BOTTOM))
-                .alignment(Alignment::Center),
-            cols[2],
-        );
+    /// All keys in a popup at center of `area`.
+    fn draw_help(area: Rect, f: &mut Frame<'_>) {
+        let key_width = KEY_HELP.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        let lines: Vec<Line<'_>> = KEY_HELP
+            .iter()
+            .map(|(key, help)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{key:>key_width$}"),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw("  "),
+                    Span::raw(*help),
+                ])
+            })
+            .collect();
 
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+        let height = lines.len() as u16 + 2;
+        let popup = area.clamp(Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        ));
+
+        f.render_widget(Clear, popup);
         f.render_widget(
-            Paragraph::new(Line::from(quit_help))
-                .block(Block::default().borders(Borders::BOTTOM | Borders::RIGHT))
-                .alignment(Alignment::Right),
-            cols[3],
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Help, any key to close"),
+            ),
+            popup,
         );
     }
 
@@ -328,6 +935,13 @@ impl<'a> UI<'a> {
     }
 
     fn draw(&self, f: &mut Frame<'_>) {
+        self.images.borrow_mut().clear();
+
+        if let Some(grid) = &self.grid {
+            grid.draw(f.area(), f);
+            return;
+        }
+
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Fill(1), Constraint::Length(4)].as_ref())
@@ -346,22 +960,64 @@ impl<'a> UI<'a> {
         let side_panel = main[0];
         let canvas = main[1];
 
+        let variation_height = if self.state.variation().is_empty() {
+            0
+        } else {
+            self.state.variation().len() as u16 + 2
+        };
+
+        // Char and filter box take 3 rows each
+        let metrics_height =
+            if side_panel.height >= MIN_LIST_HEIGHT + variation_height + METRICS_PANEL_HEIGHT + 6 {
+                METRICS_PANEL_HEIGHT
+            } else {
+                0
+            };
+
         let side_panel = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Fill(1), Constraint::Length(3)])
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(variation_height),
+                Constraint::Length(metrics_height),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ])
             .split(side_panel);
 
         let list = side_panel[0];
-        let searchbox = side_panel[1];
+        let variation = side_panel[1];
+        let metrics = side_panel[2];
+        let char_box = side_panel[3];
+        let searchbox = side_panel[4];
 
         let width = u32::from(canvas.width.saturating_sub(2));
         let height = u32::from(canvas.height.saturating_sub(2));
         self.state.update_canvas_size_by_char(width, height);
+        self.update_areas(|areas| {
+            areas.list = list;
+            areas.char_box = char_box;
+            areas.filter = searchbox;
+            areas.preview = canvas;
+        });
 
         self.draw_list(list, f);
+        if variation_height > 0 {
+            self.draw_variation(variation, f);
+        }
+        if metrics_height > 0 {
+            self.draw_metrics(metrics, f);
+        }
+        self.draw_char_input(char_box, f);
         self.draw_filter_input(searchbox, f);
         self.draw_preview(canvas, f);
         self.draw_status_bar(status_bar, f);
+
+        if self.help {
+            // Images are written over all cells, popup would be covered
+            self.images.borrow_mut().clear();
+            Self::draw_help(f.area(), f);
+        }
     }
 
     fn on_event_edit_filter(&mut self, key: KeyEvent) -> OnEventResult {
@@ -381,12 +1037,105 @@ impl<'a> UI<'a> {
         }
     }
 
+    /// New char is used after Enter, because querying fonts is slow.
+    fn on_event_edit_char(&mut self, key: KeyEvent) -> OnEventResult {
+        match key.code {
+            CtKeyCode::Esc => {
+                self.editing = None;
+                self.char_error = None;
+                self.char_input = tui_input::Input::new(self.state.char().0.to_string());
+                OnEventResult::ReDraw
+            }
+            CtKeyCode::Enter => {
+                match self.char_input.value().parse::<OneChar>() {
+                    Ok(c) if self.state.is_supported(c) => {
+                        self.state.change_char(c);
+                        self.editing = None;
+                        self.char_error = None;
+                        self.char_input = tui_input::Input::new(c.0.to_string());
+                    }
+                    Ok(c) => {
+                        self.char_error = Some(format!("No font for U+{:04X}", u32::from(c.0)));
+                    }
+                    Err(err) => self.char_error = Some(err.to_string()),
+                }
+                OnEventResult::ReDraw
+            }
+            _ => {
+                if let Some(change) = self.char_input.handle_event(&Event::Key(key)) {
+                    if change.value {
+                        self.char_error = None;
+                    }
+                    OnEventResult::ReDraw
+                } else {
+                    OnEventResult::Continue
+                }
+            }
+        }
+    }
+
+    /// Change query char, char box follows it.
+    fn set_char(&mut self, c: OneChar) {
+        self.state.change_char(c);
+        self.char_input = tui_input::Input::new(c.0.to_string());
+        self.char_error = None;
+    }
+
+    /// Step to another char.
+    fn browse(&mut self, step: fn(OneChar) -> Option<OneChar>) -> OnEventResult {
+        let Some(c) = step(self.state.char()) else {
+            return OnEventResult::Continue;
+        };
+        self.set_char(c);
+        OnEventResult::ReDraw
+    }
+
+    fn open_grid(&mut self) -> OnEventResult {
+        let Some(face) = self.state.current_font_face() else {
+            return OnEventResult::Continue;
+        };
+        let coordinates = self.state.variation().coordinates();
+        match GlyphGrid::new(face, coordinates, self.state.cell_aspect()) {
+            Ok(grid) => {
+                self.grid = Some(grid);
+                OnEventResult::ReDraw
+            }
+            Err(err) => {
+                log::warn!("Fail to read glyphs of {}: {}", face.name, err);
+                OnEventResult::Continue
+            }
+        }
+    }
+
+    fn on_event_grid(&mut self, key: KeyEvent) -> OnEventResult {
+        let Some(grid) = self.grid.as_mut() else {
+            return OnEventResult::Continue;
+        };
+        match grid.on_key(key) {
+            GridEvent::Continue => OnEventResult::Continue,
+            GridEvent::ReDraw => OnEventResult::ReDraw,
+            GridEvent::Close => {
+                self.grid = None;
+                OnEventResult::ReDraw
+            }
+            GridEvent::Select(c) => {
+                self.grid = None;
+                self.set_char(c);
+                OnEventResult::ReDraw
+            }
+        }
+    }
+
     fn on_event_normal(&mut self, key: KeyEvent) -> OnEventResult {
         if key.modifiers.contains(CtKM::ALT) || key.modifiers.contains(CtKM::CONTROL) {
             OnEventResult::Continue
         } else {
             match key.code {
                 CtKeyCode::Char('q') => OnEventResult::Exit,
+                CtKeyCode::Char('?') => {
+                    self.help = true;
+                    OnEventResult::ReDraw
+                }
                 CtKeyCode::Left | CtKeyCode::Char('h') => {
                     self.state.prev_render_type();
                     OnEventResult::ReDraw
@@ -403,25 +1152,161 @@ impl<'a> UI<'a> {
                     self.state.move_up();
                     OnEventResult::ReDraw
                 }
+                CtKeyCode::Char('[') => {
+                    self.state.variation_mut().select_prev();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char(']') => {
+                    self.state.variation_mut().select_next();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('-') => {
+                    self.state.variation_mut().adjust(-1.0);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('=') | CtKeyCode::Char('+') => {
+                    self.state.variation_mut().adjust(1.0);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('0') => {
+                    self.state.variation_mut().reset();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('e') => {
+                    self.state.toggle_em_box();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('d') if self.state.get_render_type() == &RenderType::Mono => {
+                    self.state.next_dither();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char(',') | CtKeyCode::Char('<')
+                    if self.state.get_render_type() == &RenderType::Mono =>
+                {
+                    self.state.adjust_threshold(-THRESHOLD_STEP);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('.') | CtKeyCode::Char('>')
+                    if self.state.get_render_type() == &RenderType::Mono =>
+                {
+                    self.state.adjust_threshold(THRESHOLD_STEP);
+                    OnEventResult::ReDraw
+                }
                 CtKeyCode::Char('s') | CtKeyCode::Char('/') => {
                     self.editing = Some(WhichInput::Search);
                     OnEventResult::ReDraw
                 }
+                CtKeyCode::Char('n') => self.browse(OneChar::next_scalar),
+                CtKeyCode::Char('p') => self.browse(OneChar::prev_scalar),
+                CtKeyCode::Char('N') => self.browse(OneChar::next_assigned),
+                CtKeyCode::Char('P') => self.browse(OneChar::prev_assigned),
+                CtKeyCode::Char('}') => self.browse(OneChar::next_block),
+                CtKeyCode::Char('{') => self.browse(OneChar::prev_block),
+                CtKeyCode::Char('g') => self.open_grid(),
+                CtKeyCode::Char('m') => {
+                    self.state.toggle_mark();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('v') => {
+                    self.state.toggle_view(View::Compare);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('r') => {
+                    self.state.pin_reference();
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('o') => {
+                    self.state.toggle_view(View::Overlay);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('w') => {
+                    self.state.toggle_view(View::Waterfall);
+                    OnEventResult::ReDraw
+                }
+                CtKeyCode::Char('c') => {
+                    self.editing = Some(WhichInput::Char);
+                    OnEventResult::ReDraw
+                }
                 _ => OnEventResult::Continue,
             }
         }
     }
 
+    /// Select the clicked font in list.
+    fn click_list(&mut self, row: u16) -> OnEventResult {
+        let list = self.areas.get().list;
+        // Top border is not an item
+        let Some(row) = row.checked_sub(list.y + 1) else {
+            return OnEventResult::Continue;
+        };
+        let index = self.state.mut_list_state().offset() + usize::from(row);
+        if index < self.state.len() {
+            self.state.select(Some(index));
+            OnEventResult::ReDraw
+        } else {
+            OnEventResult::Continue
+        }
+    }
+
+    fn on_event_mouse(&mut self, mouse: MouseEvent) -> OnEventResult {
+        let areas = self.areas.get();
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(button) if areas.render_label.contains(position) => {
+                if button == MouseButton::Right {
+                    self.state.prev_render_type();
+                } else {
+                    self.state.next_render_type();
+                }
+                OnEventResult::ReDraw
+            }
+            MouseEventKind::Down(MouseButton::Left) if areas.list.contains(position) => {
+                self.click_list(mouse.row)
+            }
+            MouseEventKind::Down(MouseButton::Left) if areas.filter.contains(position) => {
+                self.editing = Some(WhichInput::Search);
+                OnEventResult::ReDraw
+            }
+            MouseEventKind::Down(MouseButton::Left) if areas.char_box.contains(position) => {
+                self.editing = Some(WhichInput::Char);
+                OnEventResult::ReDraw
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = mouse.kind == MouseEventKind::ScrollUp;
+                if areas.list.contains(position) {
+                    if up {
+                        self.state.move_up();
+                    } else {
+                        self.state.move_down();
+                    }
+                } else if areas.preview.contains(position) && self.state.view() == View::Single {
+                    self.state.zoom_by(if up { 1 } else { -1 });
+                } else {
+                    return OnEventResult::Continue;
+                }
+                OnEventResult::ReDraw
+            }
+            _ => OnEventResult::Continue,
+        }
+    }
+
     fn on_event(&mut self, event: IoResult<TerminalEvent>) -> IoResult<OnEventResult> {
         match event? {
-            TerminalEvent::Tick => {
-                self.idle_redraw += 1;
-                Ok(if self.idle_redraw == 10 {
-                    OnEventResult::ReDraw
-                } else {
-                    OnEventResult::Continue
-                })
+            TerminalEvent::Resize | TerminalEvent::FocusGained => {
+                // Cell size may change with window or font size
+                self.state.update_cell_size();
+                Ok(OnEventResult::Refresh)
             }
+            TerminalEvent::FocusLost => Ok(OnEventResult::Continue),
+            TerminalEvent::Key(_) if self.help => {
+                self.help = false;
+                Ok(OnEventResult::ReDraw)
+            }
+            TerminalEvent::Mouse(_) if self.help => Ok(OnEventResult::Continue),
+            TerminalEvent::Key(key) if self.grid.is_some() => Ok(self.on_event_grid(key)),
+            // Grid has no mouse support
+            TerminalEvent::Mouse(_) if self.grid.is_some() => Ok(OnEventResult::Continue),
+            TerminalEvent::Mouse(mouse) => Ok(self.on_event_mouse(mouse)),
             TerminalEvent::Key(key) => match key.code {
                 CtKeyCode::Up => {
                     self.state.move_up();
@@ -433,6 +1318,7 @@ impl<'a> UI<'a> {
                 }
                 _ => match self.editing {
                     Some(WhichInput::Search) => Ok(self.on_event_edit_filter(key)),
+                    Some(WhichInput::Char) => Ok(self.on_event_edit_char(key)),
                     None => Ok(self.on_event_normal(key)),
                 },
             },
@@ -442,7 +1328,12 @@ impl<'a> UI<'a> {
     fn setup() -> IoResult<Terminal<CrosstermBackend<Stdout>>> {
         enable_raw_mode()?;
         let mut stdout = std::io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange
+        )?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
         terminal.hide_cursor()?;
@@ -453,17 +1344,86 @@ impl<'a> UI<'a> {
     fn shutdown(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> IoResult<()> {
         terminal.show_cursor()?;
         let backend = terminal.backend_mut();
-        execute!(backend, LeaveAlternateScreen)?;
+        execute!(
+            backend,
+            DisableFocusChange,
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        )?;
         disable_raw_mode()?;
         Ok(())
     }
 
+    /// Remove shown images from screen.
+    fn remove_images(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> IoResult<()> {
+        let shown = std::mem::take(&mut self.shown_images);
+        if shown.is_empty() {
+            return Ok(());
+        }
+
+        match self.state.protocol().and_then(|p| p.clear()) {
+            Some(clear) => {
+                let backend = terminal.backend_mut();
+                write!(backend, "{clear}")?;
+                backend.flush()
+            }
+            // Image is a part of screen content, write cells under it again to cover it
+            None => {
+                let frame = &self.frame;
+                let cells = shown
+                    .iter()
+                    .flat_map(|image| image.area.intersection(frame.area).positions())
+                    .map(|p| (p.x, p.y, &frame[p]));
+                let backend = terminal.backend_mut();
+                ratatui::backend::Backend::draw(backend, cells)?;
+                backend.flush()
+            }
+        }
+    }
+
+    /// Write images of current frame if they change, ratatui do not know them so must be done
+    /// after each draw.
+    fn flush_images(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> IoResult<()> {
+        let images = self.images.borrow().clone();
+        if images == self.shown_images {
+            return Ok(());
+        }
+
+        self.remove_images(terminal)?;
+
+        let backend = terminal.backend_mut();
+        for image in &images {
+            if let Ok(CachedGlyph {
+                content: GlyphCache::Image(glyph),
+                ..
+            }) = image.glyph.as_ref()
+            {
+                queue!(backend, MoveTo(image.area.x, image.area.y))?;
+                write!(backend, "{}", glyph.data)?;
+            }
+        }
+        backend.flush()?;
+
+        self.shown_images = images;
+        Ok(())
+    }
+
+    fn render(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> IoResult<()> {
+        let frame = terminal.draw(|f| self.draw(f))?;
+        self.frame = frame.buffer.clone();
+        self.flush_images(terminal)
+    }
+
     pub fn show(mut self) -> IoResult<()> {
         let mut terminal = Self::setup()?;
 
-        let events = TerminalEventStream::new(Duration::from_millis(1000 / 60));
+        // Query terminal before event thread starts, so answers are not read as key events
+        self.state.set_protocol(self.graphics.protocol());
+        self.state.detect_cell_size();
 
-        terminal.draw(|f| self.draw(f))?;
+        let events = TerminalEventStream::new();
+
+        self.render(&mut terminal)?;
 
         for event in events.iter() {
             match self.on_event(event) {
@@ -473,11 +1433,17 @@ impl<'a> UI<'a> {
                 }
                 Ok(result) => match result {
                     OnEventResult::ReDraw => {
-                        terminal.draw(|f| self.draw(f))?;
-                        self.idle_redraw = 0;
+                        self.render(&mut terminal)?;
+                    }
+                    OnEventResult::Refresh => {
+                        self.remove_images(&mut terminal)?;
+                        // Draw only writes changed cells, clear to write all of them
+                        terminal.clear()?;
+                        self.render(&mut terminal)?;
                     }
                     OnEventResult::Continue => (),
                     OnEventResult::Exit => {
+                        self.remove_images(&mut terminal)?;
                         return Self::shutdown(terminal);
                     }
                 },
@@ -17,6 +17,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::{
+    borrow::Cow,
     cell::{Cell, RefCell, RefMut},
     collections::hash_map::HashMap,
     rc::Rc,
@@ -24,28 +25,127 @@ use std::{
 
 use ratatui::widgets::ListState;
 
-use super::cache::{CHAR_RENDERS, CacheKey, GlyphCache, GlyphCanvasShape, MONO_RENDER, RenderType};
+use super::{
+    cache::{
+        CHAR_RENDERS, CacheKey, CachedGlyph, GlyphCache, GlyphCanvasShape, GlyphOutlineShape,
+        OVERLAY_COLORS, OutlineFit, OutlineSize, RenderType,
+    },
+    variation::Variation,
+};
 use crate::{
-    family::FilteredFamilies,
-    loader::{self, FaceInfo},
-    preview::terminal::{render::Render, ui::cache::GlyphParagraph},
-    rasterizer::{Bitmap, Rasterizer},
+    family::{self, FilteredFamilies},
+    loader::{self, AxisValue, FaceInfo, GlyphMetrics},
+    one_char::OneChar,
+    preview::terminal::{
+        cell::CellSize,
+        graphics::Protocol,
+        render::{BlockRender, ColorDepth, Dither, MonoRender, Render},
+        ui::cache::{GlyphImage, GlyphParagraph},
+    },
+    rasterizer::{self, Bitmap, Options, Size},
 };
 
-pub struct State<'a> {
-    filtered: FilteredFamilies<'a>,
+/// Face id and name, which stay the same after faces are queried again. Instances of a
+/// variable font share the id, and faces from different files may share the name.
+type FaceKey = (fontdb::ID, Cow<'static, str>);
+
+fn face_key(face: &FaceInfo) -> FaceKey {
+    (face.id, face.name.clone())
+}
+
+fn is_face(face: &FaceInfo, (id, name): &FaceKey) -> bool {
+    face.id == *id && face.name == *name
+}
+
+/// Face index and variation key
+type MetricsKey = ((usize, usize), Vec<u32>);
+
+/// Ascender and descender in ems
+type Ems = (f32, f32);
+
+/// Glyph scale of one zoom step
+const ZOOM_STEP: f32 = 1.25;
+
+/// What preview area shows.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum View {
+    /// Selected face
+    Single,
+    /// Marked faces side by side
+    Compare,
+    /// Selected face over reference face
+    Overlay,
+    /// Selected face at some fixed sizes
+    Waterfall,
+}
+
+/// Face to render and how to scale it.
+struct Target {
+    index: (usize, usize),
+    coordinates: Vec<AxisValue>,
+    em_box: bool,
+    /// Ascender and descender in ems shared by compared faces
+    shared_em: Option<Ems>,
+    /// Reference face overlaid on the glyph
+    reference: Option<(usize, usize)>,
+    /// Pixel height of glyph, instead of fitting canvas height
+    size: Option<u32>,
+    /// Glyph scale relative to fitting canvas height, if `size` is not set
+    zoom: f32,
+}
+
+pub struct State {
+    c: OneChar,
+    /// Axis values given by user, used to query faces of new char
+    user_values: Vec<AxisValue>,
+    /// Faces of the char last checked by [`Self::is_supported`], taken by following
+    /// [`Self::change_char`] so they are not queried twice
+    queried: Option<(char, Vec<FaceInfo>)>,
+    /// Name of face selected before changing char
+    face_name: Option<String>,
+    /// Faces marked to compare, in marked order
+    marked: Vec<FaceKey>,
+    /// Face to overlay selected one on
+    reference: Option<FaceKey>,
+    view: View,
+    filtered: FilteredFamilies,
     index_map: Vec<(usize, usize)>,
     name_width_max: usize,
     list_state: RefCell<ListState>,
     height: Cell<u32>,
     width: Cell<u32>,
     rt: RenderType,
-    cache: RefCell<HashMap<CacheKey, Rc<Result<GlyphCache, &'static str>>>>,
+    variation: Variation,
+    em_box: bool,
+    /// Glyph size relative to preview area is [`ZOOM_STEP`] to the power of this
+    zoom: i32,
+    threshold: u8,
+    dither: Dither,
+    color_depth: ColorDepth,
+    protocol: Option<Protocol>,
+    /// Cell aspect ratio given by user
+    cell_aspect: Option<f32>,
+    /// Cell size queried at startup, used if window pixel size is unknown
+    queried_cell: Option<CellSize>,
+    cell_size: Cell<CellSize>,
+    cache: RefCell<HashMap<CacheKey, Rc<Result<CachedGlyph, &'static str>>>>,
+    metrics_cache: RefCell<HashMap<MetricsKey, Option<Rc<GlyphMetrics>>>>,
+    ems_cache: RefCell<HashMap<(usize, usize), Option<Ems>>>,
 }
 
-impl<'a> State<'a> {
-    pub fn new(filtered: FilteredFamilies<'a>) -> Self {
+impl State {
+    pub fn new(
+        c: OneChar, filtered: FilteredFamilies, user_values: Vec<AxisValue>,
+        cell_aspect: Option<f32>,
+    ) -> Self {
         let mut ret = Self {
+            c,
+            user_values,
+            queried: None,
+            face_name: None,
+            marked: Vec::new(),
+            reference: None,
+            view: View::Single,
             filtered,
             index_map: Vec::new(),
             name_width_max: 0,
@@ -53,7 +153,19 @@ impl<'a> State<'a> {
             height: Cell::new(0),
             width: Cell::new(0),
             rt: RenderType::Mono,
+            variation: Variation::default(),
+            em_box: false,
+            zoom: 0,
+            threshold: 128,
+            dither: Dither::None,
+            color_depth: ColorDepth::detect(),
+            protocol: None,
+            cell_aspect,
+            queried_cell: None,
+            cell_size: Cell::new(CellSize::fallback(cell_aspect)),
             cache: RefCell::default(),
+            metrics_cache: RefCell::default(),
+            ems_cache: RefCell::default(),
         };
 
         ret.update_search_box(None);
@@ -84,97 +196,474 @@ impl<'a> State<'a> {
         } else {
             self.list_state.borrow_mut().select(None);
         }
+
+        self.reload_variation();
+    }
+
+    pub fn char(&self) -> OneChar {
+        self.c
+    }
+
+    /// Faces which support `c`.
+    fn query(&mut self, c: char) -> Vec<FaceInfo> {
+        match self.queried.take() {
+            Some((queried, faces)) if queried == c => faces,
+            _ => loader::query(c, &self.user_values),
+        }
+    }
+
+    pub fn is_supported(&mut self, c: OneChar) -> bool {
+        let faces = self.query(c.0);
+        let supported = !faces.is_empty();
+        self.queried = Some((c.0, faces));
+        supported
+    }
+
+    /// Show faces which support `c`, keep filter keyword, render mode, and selected face if it
+    /// supports `c` too.
+    pub fn change_char(&mut self, c: OneChar) {
+        if c == self.c {
+            return;
+        }
+
+        let faces = self.query(c.0);
+
+        // Remember the face even if new char has no font, so browsing can come back to it
+        if let Some(name) = self.current_name() {
+            self.face_name = Some(name.to_string());
+        }
+        let keyword = self.filtered.keyword().to_string();
+        self.filtered = FilteredFamilies::new(family::group_by_family_sort_by_name(faces), keyword);
+        self.c = c;
+
+        // Cache keys are indices of faces, which are changed
+        self.cache.borrow_mut().clear();
+        self.metrics_cache.borrow_mut().clear();
+        self.ems_cache.borrow_mut().clear();
+
+        self.update_search_box(None);
+        let index = self
+            .face_name
+            .as_deref()
+            .and_then(|name| self.font_face_names().position(|n| n == name));
+        if index.is_some() {
+            self.select(index);
+        }
+    }
+
+    fn reload_variation(&mut self) {
+        self.variation = self
+            .current_font_face()
+            .map(Variation::new)
+            .unwrap_or_default();
     }
 
     fn cache_index(&self) -> Option<(usize, usize)> {
         Some(self.index_map[self.index()?])
     }
 
-    fn cache_key(&self, width: u32, height: u32) -> Option<CacheKey> {
-        Some(CacheKey {
-            index: self.cache_index()?,
+    /// Index of selected face in families.
+    pub fn current_index(&self) -> Option<(usize, usize)> {
+        self.cache_index()
+    }
+
+    fn cache_key(&self, target: &Target, width: u32, height: u32) -> CacheKey {
+        CacheKey {
+            index: target.index,
             rt: self.rt,
             width,
             height,
-        })
+            variation: target
+                .coordinates
+                .iter()
+                .map(|c| c.value.to_bits())
+                .collect(),
+            em_box: target.em_box,
+            hscale: self.hscale().to_bits(),
+            threshold: self.threshold,
+            dither: self.dither,
+            shared_em: target.shared_em.map(|(a, d)| [a.to_bits(), d.to_bits()]),
+            reference: target.reference,
+            size: target.size,
+            zoom: target.zoom.to_bits(),
+        }
+    }
+
+    /// Whether any font supports current char, ignoring the filter.
+    pub fn has_fonts(&self) -> bool {
+        !self.filtered.is_empty()
     }
 
     pub fn len(&self) -> usize {
         self.index_map.len()
     }
 
-    pub fn render(&self) -> Option<Rc<Result<GlyphCache, &'static str>>> {
-        let (width, height) = match self.rt {
-            RenderType::Mono => self.get_canvas_size_by_pixel(),
-            _ => self.get_canvas_size_by_char(),
+    /// Glyph of selected face in preview area.
+    pub fn render(&self) -> Option<Rc<Result<CachedGlyph, &'static str>>> {
+        let (width, height) = self.get_canvas_size_by_char();
+        let target = Target {
+            index: self.cache_index()?,
+            coordinates: self.variation.coordinates(),
+            em_box: self.em_box,
+            shared_em: None,
+            reference: None,
+            size: None,
+            zoom: self.zoom(),
         };
+        Some(self.render_target(&target, width, height))
+    }
 
-        let key = self.cache_key(width, height)?;
+    /// Chars (width, height) which a glyph of `size` pixels height needs.
+    pub fn waterfall_cell(&self, size: u32) -> (u32, u32) {
+        let (cols, rows) = self.pixels_per_char();
+        // Em box is about as wide as it is high
+        let mut width = (size as f32 * self.hscale()).ceil() as u32;
+        // Moon emoji are double width
+        if self.rt == RenderType::Moon {
+            width *= 2;
+        }
+        (width.div_ceil(cols), size.div_ceil(rows))
+    }
 
-        let glyph = self
-            .cache
-            .borrow_mut()
-            .entry(key)
-            .or_insert_with(|| {
-                Rc::new(
-                    self.real_render(width, height)
-                        .expect("render can't be null because cache key exist"),
-                )
-            })
-            .clone();
+    /// Glyph of selected face at `size` pixels height, in a cell of `width` x `height` chars.
+    pub fn render_waterfall(
+        &self, size: u32, width: u32, height: u32,
+    ) -> Option<Rc<Result<CachedGlyph, &'static str>>> {
+        let target = Target {
+            index: self.cache_index()?,
+            coordinates: self.variation.coordinates(),
+            em_box: self.em_box,
+            shared_em: None,
+            reference: None,
+            size: Some(size),
+            zoom: 1.0,
+        };
+        Some(self.render_target(&target, width, height))
+    }
 
-        Some(glyph)
+    /// Glyph of selected face over reference face at the same scale, `None` if there is no
+    /// selected face or reference face does not support current char.
+    pub fn render_overlay(&self) -> Option<Rc<Result<CachedGlyph, &'static str>>> {
+        let index = self.cache_index()?;
+        let reference = self.find_key(self.reference.as_ref()?)?;
+        let target = Target {
+            index,
+            coordinates: self.variation.coordinates(),
+            em_box: self.em_box,
+            shared_em: Some(self.shared_em([index, reference].into_iter())),
+            reference: Some(reference),
+            size: None,
+            zoom: 1.0,
+        };
+        let (width, height) = self.get_canvas_size_by_char();
+        Some(self.render_target(&target, width, height))
     }
 
-    fn rasterize(&self, _width: u32, height: u32) -> Option<Result<Bitmap, &'static str>> {
-        let info = self.current_font_face()?;
+    /// Glyph of a compared face in a cell of `width` x `height` chars.
+    pub fn render_compare(
+        &self, index: (usize, usize), width: u32, height: u32, shared_em: Ems,
+    ) -> Rc<Result<CachedGlyph, &'static str>> {
+        // Selected face follows the adjusted variation
+        let coordinates = if self.cache_index() == Some(index) {
+            self.variation.coordinates()
+        } else {
+            self.get_font_face(index).coordinates.clone()
+        };
+        // Shared em box keeps baselines of compared faces at the same row
+        let target = Target {
+            index,
+            coordinates,
+            em_box: true,
+            shared_em: Some(shared_em),
+            reference: None,
+            size: None,
+            zoom: 1.0,
+        };
+        self.render_target(&target, width, height)
+    }
 
-        let scale = if matches!(self.rt, RenderType::AsciiLevel10 | RenderType::AsciiLevel70) {
-            Some(2.0)
+    /// Pixels (cols, rows) of current render in a char.
+    fn pixels_per_char(&self) -> (u32, u32) {
+        if self.rt == RenderType::Image {
+            let cell = self.cell_size.get();
+            (cell.width, cell.height)
         } else {
-            None
+            self.rt.cell_size()
+        }
+    }
+
+    fn render_target(
+        &self, target: &Target, width: u32, height: u32,
+    ) -> Rc<Result<CachedGlyph, &'static str>> {
+        let (cols, rows) = self.pixels_per_char();
+        let (width, height) = (width * cols, height * rows);
+
+        self.cache
+            .borrow_mut()
+            .entry(self.cache_key(target, width, height))
+            .or_insert_with(|| Rc::new(self.real_render(target, width, height)))
+            .clone()
+    }
+
+    fn rasterize(&self, target: &Target, height: u32) -> Result<Bitmap, &'static str> {
+        let info = self.get_font_face(target.index);
+
+        // Glyph fits the height with its ascender and descender, in em box or not
+        let height = target
+            .size
+            .unwrap_or_else(|| (height as f32 * target.zoom).round() as u32);
+        let options = Options {
+            hscale: self.hscale(),
+            // Terminal background is usually dark
+            foreground: [0xC0, 0xC0, 0xC0, 0xFF],
+            em_box: target.em_box,
+            shared_em: target.shared_em,
+            ..Options::new(Size::Height(height))
         };
 
-        let bitmap: Result<Bitmap, &'static str> = loader::database()
-            .with_face_data(info.id, |data, index| -> Result<Bitmap, &'static str> {
-                let mut r = Rasterizer::new(data, index).map_err(|_| "Can't pare font file")?;
-                r.set_pixel_height(height);
-                if let Some(scale) = scale {
-                    r.set_hscale(scale);
-                }
-                r.rasterize(info.gid)
-                    .ok_or("Can't get target glyph from this font")
-            })
-            .unwrap_or(Err("Can't read font file"));
+        rasterizer::rasterize_face(info, &target.coordinates, options).map_err(|e| e.message())
+    }
 
-        Some(bitmap)
+    /// Outline render reads contours directly, without rasterizing.
+    fn render_outline(
+        &self, target: &Target, width: u32, height: u32,
+    ) -> Result<CachedGlyph, &'static str> {
+        let face = self.get_font_face(target.index);
+        let fit = match target.shared_em {
+            Some((ascender, descender)) => OutlineFit::SharedEm(ascender, descender),
+            None if target.em_box => OutlineFit::EmBox,
+            None => OutlineFit::Points,
+        };
+
+        match face.glyph_outline(&target.coordinates) {
+            Ok(outline) if outline.contours.is_empty() => Err("Target glyph has no outline"),
+            Ok(outline) => Ok(CachedGlyph {
+                content: GlyphCache::Outline(GlyphOutlineShape::new(
+                    outline,
+                    (f64::from(width), f64::from(height)),
+                    f64::from(self.hscale()),
+                    fit,
+                    match target.size {
+                        Some(size) => OutlineSize::Pixels(f64::from(size)),
+                        None => OutlineSize::Zoom(f64::from(target.zoom)),
+                    },
+                )),
+                strike: None,
+                overlap: None,
+            }),
+            Err(_) => Err("Can't parse font file"),
+        }
     }
 
-    fn real_render(&self, width: u32, height: u32) -> Option<Result<GlyphCache, &'static str>> {
-        let bitmap = self.rasterize(width, height)?;
+    /// Overlay needs both bitmaps, so outline render is not supported.
+    fn render_overlaid(
+        &self, target: &Target, reference: (usize, usize), width: u32, height: u32,
+    ) -> Result<CachedGlyph, &'static str> {
+        if self.rt == RenderType::Outline {
+            return Err("Overlay is not supported in outline render");
+        }
+
+        let other = Target {
+            index: reference,
+            coordinates: self.get_font_face(reference).coordinates.clone(),
+            reference: None,
+            ..*target
+        };
+        let bitmap = self.rasterize(target, height)?;
+        let (bitmap, overlap) = bitmap.overlay(&self.rasterize(&other, height)?, OVERLAY_COLORS);
 
-        let cache = bitmap.map(|bitmap| match self.rt {
+        Ok(CachedGlyph {
+            content: self.to_glyph_cache(&bitmap, width, height),
+            strike: None,
+            overlap: Some(overlap),
+        })
+    }
+
+    fn real_render(
+        &self, target: &Target, width: u32, height: u32,
+    ) -> Result<CachedGlyph, &'static str> {
+        if let Some(reference) = target.reference {
+            return self.render_overlaid(target, reference, width, height);
+        }
+        if self.rt == RenderType::Outline {
+            return self.render_outline(target, width, height);
+        }
+
+        self.rasterize(target, height).map(|bitmap| CachedGlyph {
+            content: self.to_glyph_cache(&bitmap, width, height),
+            strike: bitmap.strike(),
+            overlap: None,
+        })
+    }
+
+    fn to_glyph_cache(&self, bitmap: &Bitmap, width: u32, height: u32) -> GlyphCache {
+        match self.rt {
             RenderType::Mono => GlyphCache::Canvas(GlyphCanvasShape::new(
-                MONO_RENDER.render(&bitmap),
+                MonoRender::new(self.threshold, self.dither).render(bitmap),
+                bitmap,
                 width as f64,
                 height as f64,
             )),
+            rt @ (RenderType::HalfBlock | RenderType::Quadrant | RenderType::Sextant) => {
+                let block = rt.block().expect("block render type");
+                GlyphCache::Paragraph(GlyphParagraph::from_cells(
+                    BlockRender::new(block).render(bitmap),
+                    bitmap,
+                    block.cell_size(),
+                    self.color_depth,
+                ))
+            }
+            RenderType::Outline => unreachable!("outline is not rasterized"),
+            RenderType::Image => GlyphCache::Image(GlyphImage::new(
+                bitmap,
+                width,
+                height,
+                self.protocol
+                    .expect("image render is only used with a protocol"),
+            )),
             rt => GlyphCache::Paragraph(GlyphParagraph::new(
                 CHAR_RENDERS
                     .get(&rt)
                     .expect("all render must be exist")
-                    .render(&bitmap),
+                    .render(bitmap),
+                bitmap,
             )),
-        });
+        }
+    }
+
+    /// Metrics of target glyph in current font face, at current variation.
+    pub fn glyph_metrics(&self) -> Option<Rc<GlyphMetrics>> {
+        let key = (self.cache_index()?, self.variation.key());
 
-        Some(cache)
+        self.metrics_cache
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| {
+                let face = self.current_font_face()?;
+                match face.glyph_metrics(&self.variation.coordinates()) {
+                    Ok(metrics) => Some(Rc::new(metrics)),
+                    Err(err) => {
+                        log::warn!("Fail to read glyph metrics of {}: {}", face.name, err);
+                        None
+                    }
+                }
+            })
+            .clone()
     }
 
-    fn get_font_face(&self, (i, x): (usize, usize)) -> &'a FaceInfo {
-        self.filtered.data()[i].faces[x]
+    /// Contour, on-curve and off-curve point counts of current outline render.
+    pub fn outline_summary(&self) -> Option<(usize, usize, usize)> {
+        let glyph = self.render()?;
+        match glyph.as_ref().as_ref().ok()?.content {
+            GlyphCache::Outline(ref shape) => {
+                let outline = shape.outline();
+                let (on, off) = outline.point_count();
+                Some((outline.contours.len(), on, off))
+            }
+            _ => None,
+        }
     }
 
-    fn current_font_face(&self) -> Option<&'a FaceInfo> {
+    /// Embedded bitmap strike size used by current preview, if any.
+    pub fn current_strike(&self) -> Option<u16> {
+        self.render()?.as_ref().as_ref().ok()?.strike
+    }
+
+    pub fn is_marked(&self, face: &FaceInfo) -> bool {
+        self.marked.iter().any(|key| is_face(face, key))
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.marked.len()
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let Some(face) = self.current_font_face() else {
+            return;
+        };
+        if let Some(i) = self.marked.iter().position(|key| is_face(face, key)) {
+            self.marked.remove(i);
+        } else {
+            self.marked.push(face_key(face));
+        }
+    }
+
+    pub fn view(&self) -> View {
+        self.view
+    }
+
+    /// Switch to `view`, or back to single face if it is shown now.
+    pub fn toggle_view(&mut self, view: View) {
+        self.view = if self.view == view {
+            View::Single
+        } else {
+            view
+        };
+    }
+
+    /// Name of reference face.
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_ref().map(|(_, name)| name.as_ref())
+    }
+
+    pub fn is_reference(&self, face: &FaceInfo) -> bool {
+        self.reference
+            .as_ref()
+            .is_some_and(|key| is_face(face, key))
+    }
+
+    /// Use selected face as reference, or unpin it if it is the reference now.
+    pub fn pin_reference(&mut self) {
+        let Some(face) = self.current_font_face() else {
+            return;
+        };
+        self.reference = if self.is_reference(face) {
+            None
+        } else {
+            Some(face_key(face))
+        };
+    }
+
+    /// Index of the face, it may be hidden by the filter.
+    fn find_key(&self, key: &FaceKey) -> Option<(usize, usize)> {
+        self.filtered.data().iter().enumerate().find_map(|(i, f)| {
+            let x = f.faces.iter().position(|face| is_face(face, key))?;
+            Some((i, x))
+        })
+    }
+
+    /// Names of marked faces with their index, `None` if it does not support current char.
+    /// Filter is ignored.
+    pub fn compared_faces(&self) -> Vec<(&str, Option<(usize, usize)>)> {
+        self.marked
+            .iter()
+            .map(|key| (key.1.as_ref(), self.find_key(key)))
+            .collect()
+    }
+
+    /// Largest ascender and descender of faces in ems, which fit all of them in one scale.
+    pub fn shared_em(&self, indices: impl Iterator<Item = (usize, usize)>) -> Ems {
+        let mut ems = self.ems_cache.borrow_mut();
+        indices
+            .filter_map(|index| {
+                *ems.entry(index).or_insert_with(|| {
+                    let face = self.get_font_face(index);
+                    face.vertical_ems()
+                        .inspect_err(|err| {
+                            log::warn!("Fail to read metrics of {}: {}", face.name, err)
+                        })
+                        .ok()
+                })
+            })
+            .reduce(|(a1, d1), (a2, d2)| (a1.max(a2), d1.min(d2)))
+            .unwrap_or((1.0, 0.0))
+    }
+
+    fn get_font_face(&self, (i, x): (usize, usize)) -> &FaceInfo {
+        &self.filtered.data()[i].faces[x]
+    }
+
+    pub fn current_font_face(&self) -> Option<&FaceInfo> {
         Some(self.get_font_face(self.cache_index()?))
     }
 
@@ -186,11 +675,16 @@ impl<'a> State<'a> {
         self.name_width_max
     }
 
-    pub fn font_face_names(&self) -> impl Iterator<Item = &str> {
+    /// Faces in list, in shown order.
+    pub fn font_faces(&self) -> impl Iterator<Item = &FaceInfo> {
         self.index_map
             .iter()
             .copied()
-            .map(|index| self.get_font_face(index).name.as_ref())
+            .map(|index| self.get_font_face(index))
+    }
+
+    pub fn font_face_names(&self) -> impl Iterator<Item = &str> {
+        self.font_faces().map(|face| face.name.as_ref())
     }
 
     pub fn mut_list_state(&self) -> RefMut<'_, ListState> {
@@ -207,7 +701,7 @@ impl<'a> State<'a> {
             .borrow()
             .selected()
             .map(|index| index.saturating_sub(1));
-        self.list_state.borrow_mut().select(changed);
+        self.select(changed);
     }
 
     pub fn move_down(&mut self) {
@@ -216,31 +710,148 @@ impl<'a> State<'a> {
             .borrow()
             .selected()
             .map(|index| index.saturating_add(1).min(self.len().saturating_sub(1)));
-        self.list_state.borrow_mut().select(changed);
+        self.select(changed);
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        if self.index() != index {
+            self.list_state.borrow_mut().select(index);
+            self.reload_variation();
+        }
+    }
+
+    pub fn variation(&self) -> &Variation {
+        &self.variation
+    }
+
+    pub fn variation_mut(&mut self) -> &mut Variation {
+        &mut self.variation
+    }
+
+    pub fn em_box(&self) -> bool {
+        self.em_box
+    }
+
+    pub fn toggle_em_box(&mut self) {
+        self.em_box = !self.em_box
+    }
+
+    pub fn zoom(&self) -> f32 {
+        ZOOM_STEP.powi(self.zoom)
+    }
+
+    /// Zoom in or out by `steps`, from about a quarter to 8 times of preview area.
+    pub fn zoom_by(&mut self, steps: i32) {
+        self.zoom = (self.zoom + steps).clamp(-6, 9)
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn adjust_threshold(&mut self, delta: i16) {
+        self.threshold = (i16::from(self.threshold) + delta).clamp(1, 255) as u8
+    }
+
+    pub fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    pub fn next_dither(&mut self) {
+        self.dither = self.dither.next()
     }
 
     pub fn get_render_type(&self) -> &RenderType {
         &self.rt
     }
 
-    pub fn next_render_type(&mut self) {
-        self.rt = match self.rt {
+    fn next_of(rt: RenderType) -> RenderType {
+        match rt {
             RenderType::AsciiLevel10 => RenderType::AsciiLevel70,
             RenderType::AsciiLevel70 => RenderType::Moon,
             RenderType::Moon => RenderType::Mono,
-            RenderType::Mono => RenderType::AsciiLevel10,
+            RenderType::Mono => RenderType::HalfBlock,
+            RenderType::HalfBlock => RenderType::Quadrant,
+            RenderType::Quadrant => RenderType::Sextant,
+            RenderType::Sextant => RenderType::Edge,
+            RenderType::Edge => RenderType::EdgeAscii,
+            RenderType::EdgeAscii => RenderType::Image,
+            RenderType::Image => RenderType::Outline,
+            RenderType::Outline => RenderType::AsciiLevel10,
         }
     }
 
-    pub fn prev_render_type(&mut self) {
-        self.rt = match self.rt {
-            RenderType::AsciiLevel10 => RenderType::Mono,
+    fn prev_of(rt: RenderType) -> RenderType {
+        match rt {
+            RenderType::AsciiLevel10 => RenderType::Outline,
             RenderType::AsciiLevel70 => RenderType::AsciiLevel10,
             RenderType::Moon => RenderType::AsciiLevel70,
             RenderType::Mono => RenderType::Moon,
+            RenderType::HalfBlock => RenderType::Mono,
+            RenderType::Quadrant => RenderType::HalfBlock,
+            RenderType::Sextant => RenderType::Quadrant,
+            RenderType::Edge => RenderType::Sextant,
+            RenderType::EdgeAscii => RenderType::Edge,
+            RenderType::Image => RenderType::EdgeAscii,
+            RenderType::Outline => RenderType::Image,
+        }
+    }
+
+    /// Image render is skipped if terminal has no graphics protocol
+    fn available(&self, rt: RenderType) -> bool {
+        rt != RenderType::Image || self.protocol.is_some()
+    }
+
+    pub fn next_render_type(&mut self) {
+        self.rt = Self::next_of(self.rt);
+        if !self.available(self.rt) {
+            self.rt = Self::next_of(self.rt);
+        }
+    }
+
+    pub fn prev_render_type(&mut self) {
+        self.rt = Self::prev_of(self.rt);
+        if !self.available(self.rt) {
+            self.rt = Self::prev_of(self.rt);
+        }
+    }
+
+    pub fn protocol(&self) -> Option<Protocol> {
+        self.protocol
+    }
+
+    /// Use the graphics protocol, and show image by default if there is one.
+    pub fn set_protocol(&mut self, protocol: Option<Protocol>) {
+        self.protocol = protocol;
+        if protocol.is_some() {
+            self.rt = RenderType::Image;
         }
     }
 
+    /// Query cell size from terminal, must be called in raw mode before reading events.
+    pub fn detect_cell_size(&mut self) {
+        self.queried_cell = CellSize::detect();
+        self.update_cell_size();
+    }
+
+    /// Read cell size from window again, call it when window is resized or focused.
+    pub fn update_cell_size(&self) {
+        let size = CellSize::from_window()
+            .or(self.queried_cell)
+            .unwrap_or_else(|| CellSize::fallback(self.cell_aspect));
+        self.cell_size.replace(size);
+    }
+
+    /// Cell height divided by width.
+    pub fn cell_aspect(&self) -> f32 {
+        self.cell_aspect
+            .unwrap_or_else(|| self.cell_size.get().aspect())
+    }
+
+    fn hscale(&self) -> f32 {
+        self.rt.hscale(self.cell_aspect())
+    }
+
     pub fn update_canvas_size_by_char(&self, width: u32, height: u32) {
         self.width.replace(width);
         self.height.replace(height);
@@ -249,8 +860,4 @@ impl<'a> State<'a> {
     pub fn get_canvas_size_by_char(&self) -> (u32, u32) {
         (self.width.get(), self.height.get())
     }
-
-    pub fn get_canvas_size_by_pixel(&self) -> (u32, u32) {
-        (self.width.get() * 2, self.height.get() * 4)
-    }
 }
@@ -0,0 +1,118 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::loader::{Axis, AxisValue, FaceInfo};
+
+/// How many steps from min to max value of an axis
+const STEPS: f32 = 100.0;
+
+/// Variation axes of current selected face and the value user adjusted to.
+#[derive(Default)]
+pub struct Variation {
+    axes: Vec<Axis>,
+    values: Vec<f32>,
+    initial: Vec<f32>,
+    selected: usize,
+}
+
+impl Variation {
+    pub fn new(face: &FaceInfo) -> Self {
+        let axes = match face.variation_axes() {
+            Ok(axes) => axes,
+            Err(err) => {
+                log::warn!("Fail to read variation axes of {}: {}", face.name, err);
+                vec![]
+            }
+        };
+
+        let values: Vec<_> = axes
+            .iter()
+            .map(|axis| {
+                face.coordinates
+                    .iter()
+                    .find(|c| c.tag == axis.tag)
+                    .map(|c| c.value)
+                    .unwrap_or(axis.def_value)
+            })
+            .collect();
+
+        Self {
+            axes,
+            initial: values.clone(),
+            values,
+            selected: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.axes.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Axis, f32)> {
+        self.axes.iter().zip(self.values.iter().copied())
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn coordinates(&self) -> Vec<AxisValue> {
+        self.iter()
+            .map(|(axis, value)| AxisValue {
+                tag: axis.tag,
+                value,
+            })
+            .collect()
+    }
+
+    /// Values as bits, used as a part of cache key because f32 can't be hashed.
+    pub fn key(&self) -> Vec<u32> {
+        self.values.iter().map(|v| v.to_bits()).collect()
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = self
+            .selected
+            .saturating_add(1)
+            .min(self.axes.len().saturating_sub(1));
+    }
+
+    /// Move value of selected axis by `steps`, negative means decrease.
+    pub fn adjust(&mut self, steps: f32) {
+        let Some(axis) = self.axes.get(self.selected) else {
+            return;
+        };
+
+        let step = (axis.max_value - axis.min_value) / STEPS;
+        let value = &mut self.values[self.selected];
+        *value = (*value + step * steps).clamp(axis.min_value, axis.max_value);
+    }
+
+    /// Reset all axes to the value of selected instance.
+    pub fn reset(&mut self) {
+        self.values.clone_from(&self.initial);
+    }
+}
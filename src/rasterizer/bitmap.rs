@@ -19,14 +19,44 @@
 use ab_glyph::OutlinedGlyph;
 use grid::Grid;
 
+use super::guide::{EmMetrics, Guide, GuideKind};
+
 pub struct Metrics {
     pub height: usize,
     pub width: usize,
 }
 
+/// A not premultiplied RGBA color
+pub type Rgba = [u8; 4];
+
+/// Pixels covered by only one or both of two overlaid glyphs.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Overlap {
+    pub only_a: usize,
+    pub only_b: usize,
+    pub both: usize,
+}
+
+impl Overlap {
+    /// Pixels covered by both glyphs divided by pixels covered by any of them.
+    pub fn similarity(&self) -> f32 {
+        let any = self.only_a + self.only_b + self.both;
+        if any == 0 {
+            1.0
+        } else {
+            self.both as f32 / any as f32
+        }
+    }
+}
+
 pub struct Bitmap {
     metrics: Metrics,
     bitmap: Grid<u8>,
+    colors: Option<Grid<Rgba>>,
+    strike: Option<u16>,
+    /// Glyph origin (col, row) in pixels, may be outside of the bitmap
+    origin: (i32, i32),
+    guides: Vec<Guide>,
 }
 
 impl Bitmap {
@@ -45,7 +75,213 @@ impl Bitmap {
             bitmap[(y as usize, x as usize)] = value
         });
 
-        Self { metrics, bitmap }
+        Self {
+            metrics,
+            bitmap,
+            colors: None,
+            strike: None,
+            origin: (-bound.min.x as i32, -bound.min.y as i32),
+            guides: vec![],
+        }
+    }
+
+    /// Create a color bitmap, alpha channel is used as gray value of the pixel.
+    ///
+    /// Transparent rows and columns around the image are cropped,
+    /// to keep the same behavior as outline glyph.
+    ///
+    /// `origin` is position (col, row) of glyph origin in the image.
+    pub fn from_rgba(colors: Grid<Rgba>, origin: (i32, i32)) -> Self {
+        Self::from_pixels(colors, origin, true)
+    }
+
+    /// Create a bitmap without color, only alpha channel of `pixels` is used.
+    pub fn from_alpha(pixels: Grid<Rgba>, origin: (i32, i32)) -> Self {
+        Self::from_pixels(pixels, origin, false)
+    }
+
+    fn from_pixels(colors: Grid<Rgba>, origin: (i32, i32), colored: bool) -> Self {
+        let opaque = |row: usize, col: usize| colors[(row, col)][3] > 0;
+
+        let rows = (0..colors.rows()).filter(|&row| (0..colors.cols()).any(|col| opaque(row, col)));
+        let cols = (0..colors.cols()).filter(|&col| (0..colors.rows()).any(|row| opaque(row, col)));
+
+        let (top, bottom) = (rows.clone().min(), rows.max());
+        let (left, right) = (cols.clone().min(), cols.max());
+
+        let (Some(top), Some(bottom), Some(left), Some(right)) = (top, bottom, left, right) else {
+            return Self {
+                metrics: Metrics {
+                    height: 0,
+                    width: 0,
+                },
+                bitmap: Grid::new(0, 0),
+                colors: colored.then(|| Grid::new(0, 0)),
+                strike: None,
+                origin,
+                guides: vec![],
+            };
+        };
+
+        let metrics = Metrics {
+            height: bottom - top + 1,
+            width: right - left + 1,
+        };
+
+        let mut bitmap = Grid::new(metrics.height, metrics.width);
+        let mut cropped = Grid::new(metrics.height, metrics.width);
+
+        for row in 0..metrics.height {
+            for col in 0..metrics.width {
+                let color = colors[(row + top, col + left)];
+                bitmap[(row, col)] = color[3];
+                cropped[(row, col)] = color;
+            }
+        }
+
+        Self {
+            metrics,
+            bitmap,
+            colors: colored.then_some(cropped),
+            strike: None,
+            origin: (origin.0 - left as i32, origin.1 - top as i32),
+            guides: vec![],
+        }
+    }
+
+    /// Extend the bitmap to cover the whole em box, from descender to ascender and from origin
+    /// to advance, then record positions of guide lines.
+    ///
+    /// Parts of glyph outside of the em box are kept.
+    pub fn into_em_box(self, em: &EmMetrics) -> Self {
+        let (ox, oy) = self.origin;
+        let (height, width) = (self.metrics.height as i32, self.metrics.width as i32);
+
+        // Bounds relative to origin, y axis goes down
+        let top = (-em.ascender).min(-oy);
+        let bottom = (-em.descender).max(height - oy).max(top + 1);
+        let left = (-ox).min(0);
+        let right = em.advance.max(width - ox).max(left + 1);
+
+        let metrics = Metrics {
+            height: (bottom - top) as usize,
+            width: (right - left) as usize,
+        };
+
+        let (dx, dy) = ((-ox - left) as usize, (-oy - top) as usize);
+
+        let mut bitmap = Grid::new(metrics.height, metrics.width);
+        let mut colors = self
+            .colors
+            .as_ref()
+            .map(|_| Grid::new(metrics.height, metrics.width));
+
+        for row in 0..self.metrics.height {
+            for col in 0..self.metrics.width {
+                bitmap[(row + dy, col + dx)] = self.bitmap[(row, col)];
+                if let (Some(colors), Some(src)) = (colors.as_mut(), self.colors.as_ref()) {
+                    colors[(row + dy, col + dx)] = src[(row, col)];
+                }
+            }
+        }
+
+        // Lines at top or left edge of pixels, except descender and advance which are at the
+        // bottom or right edge, so they are moved into the em box by 1px
+        let inside = |kind, position: i32| Guide {
+            kind,
+            position: match kind {
+                GuideKind::Descender | GuideKind::Advance => (position - 1).max(0) as usize,
+                _ => position as usize,
+            },
+        };
+
+        let mut guides = vec![
+            inside(GuideKind::Origin, -left),
+            inside(GuideKind::Advance, em.advance - left),
+        ];
+        guides.extend(
+            em.horizontal_lines()
+                .map(|(kind, above)| inside(kind, -above - top)),
+        );
+
+        Self {
+            metrics,
+            bitmap,
+            colors,
+            strike: self.strike,
+            origin: (-left, -top),
+            guides,
+        }
+    }
+
+    /// Put `other` over this bitmap with the same glyph origin, pixels covered by more than half
+    /// are painted in `colors` of only this, only other and both. Guides of this bitmap are kept.
+    pub fn overlay(&self, other: &Self, colors: [Rgba; 3]) -> (Self, Overlap) {
+        let bounds = |b: &Self| {
+            let (ox, oy) = b.origin;
+            let (height, width) = (b.metrics.height as i32, b.metrics.width as i32);
+            (-ox, -oy, width - ox, height - oy)
+        };
+        let (l1, t1, r1, b1) = bounds(self);
+        let (l2, t2, r2, b2) = bounds(other);
+        let (left, top, right, bottom) = (l1.min(l2), t1.min(t2), r1.max(r2), b1.max(b2));
+
+        let metrics = Metrics {
+            height: (bottom - top) as usize,
+            width: (right - left) as usize,
+        };
+        // Negative positions are out of bitmap
+        let covered = |b: &Self, (dx, dy): (i32, i32), row: usize, col: usize| {
+            b.bitmap
+                .get(row as i32 - dy, col as i32 - dx)
+                .is_some_and(|&value| value >= 0x80)
+        };
+        let (d1, d2) = ((l1 - left, t1 - top), (l2 - left, t2 - top));
+
+        let mut bitmap = Grid::new(metrics.height, metrics.width);
+        let mut painted = Grid::new(metrics.height, metrics.width);
+        let mut overlap = Overlap::default();
+        for row in 0..metrics.height {
+            for col in 0..metrics.width {
+                let color = match (covered(self, d1, row, col), covered(other, d2, row, col)) {
+                    (true, false) => Some((&mut overlap.only_a, colors[0])),
+                    (false, true) => Some((&mut overlap.only_b, colors[1])),
+                    (true, true) => Some((&mut overlap.both, colors[2])),
+                    (false, false) => None,
+                };
+                if let Some((count, color)) = color {
+                    *count += 1;
+                    bitmap[(row, col)] = 0xFF;
+                    painted[(row, col)] = color;
+                }
+            }
+        }
+
+        let (dx, dy) = (d1.0 as usize, d1.1 as usize);
+        let guides = self
+            .guides
+            .iter()
+            .map(|guide| Guide {
+                kind: guide.kind,
+                position: guide.position + if guide.kind.is_vertical() { dx } else { dy },
+            })
+            .collect();
+
+        let bitmap = Self {
+            metrics,
+            bitmap,
+            colors: Some(painted),
+            strike: None,
+            origin: (-left, -top),
+            guides,
+        };
+        (bitmap, overlap)
+    }
+
+    /// Mark this bitmap as scaled from an embedded bitmap strike of `ppem` size.
+    pub fn with_strike(mut self, ppem: u16) -> Self {
+        self.strike = Some(ppem);
+        self
     }
 
     pub fn metrics(&self) -> &Metrics {
@@ -55,4 +291,25 @@ impl Bitmap {
     pub fn pixel(&self, row: usize, col: usize) -> u8 {
         self.bitmap[(row, col)]
     }
+
+    /// Colors of each pixel, `None` if this is a normal outline glyph.
+    pub fn colors(&self) -> Option<&Grid<Rgba>> {
+        self.colors.as_ref()
+    }
+
+    /// Size of embedded bitmap strike this bitmap comes from, `None` if it is rendered from
+    /// outline.
+    pub fn strike(&self) -> Option<u16> {
+        self.strike
+    }
+
+    /// Glyph origin (col, row) in pixels, may be outside of the bitmap.
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    /// Guide lines of font metrics, empty if bitmap is not extended to em box.
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
 }
@@ -0,0 +1,769 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ab_glyph_rasterizer::{Rasterizer as Coverage, point};
+use grid::Grid;
+use ttf_parser::{
+    Face, GlyphId, OutlineBuilder, RgbaColor, Transform,
+    colr::{ClipBox, ColorStop, CompositeMode, GradientExtend, Paint, Painter},
+};
+
+use super::bitmap::{Bitmap, Rgba};
+
+/// Premultiplied color, components are in 0.0 ~ 1.0
+#[derive(Debug, Copy, Clone, Default)]
+struct Color {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+}
+
+impl Color {
+    fn new(c: RgbaColor) -> Self {
+        let a = f32::from(c.alpha) / 255.0;
+        Self {
+            r: f32::from(c.red) / 255.0 * a,
+            g: f32::from(c.green) / 255.0 * a,
+            b: f32::from(c.blue) / 255.0 * a,
+            a,
+        }
+    }
+
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+            a: f(self.a),
+        }
+    }
+
+    fn zip(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        Self {
+            r: f(self.r, other.r),
+            g: f(self.g, other.g),
+            b: f(self.b, other.b),
+            a: f(self.a, other.a),
+        }
+    }
+
+    fn scale(self, k: f32) -> Self {
+        self.map(|c| c * k)
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.zip(other, |a, b| a + (b - a) * t)
+    }
+
+    fn rgba(self) -> Rgba {
+        if self.a <= 0.0 {
+            return [0; 4];
+        }
+
+        let unpremultiply = |c: f32| ((c / self.a).clamp(0.0, 1.0) * 255.0).round() as u8;
+        [
+            unpremultiply(self.r),
+            unpremultiply(self.g),
+            unpremultiply(self.b),
+            (self.a.clamp(0.0, 1.0) * 255.0).round() as u8,
+        ]
+    }
+}
+
+/// Compose `src` onto `dst`, both are premultiplied.
+fn composite(mode: CompositeMode, src: Color, dst: Color) -> Color {
+    let (sa, da) = (src.a, dst.a);
+
+    let porter_duff = |fs: f32, fd: f32| src.scale(fs).zip(dst.scale(fd), |s, d| s + d);
+
+    // Separable blend mode, see https://www.w3.org/TR/compositing-1/#blending
+    let blend = |f: fn(f32, f32) -> f32| {
+        let unpremultiply = |c: f32, a: f32| if a > 0.0 { c / a } else { 0.0 };
+        let mixed = |s: f32, d: f32| {
+            (1.0 - da) * s
+                + (1.0 - sa) * d
+                + sa * da * f(unpremultiply(s, sa), unpremultiply(d, da))
+        };
+        Color {
+            r: mixed(src.r, dst.r),
+            g: mixed(src.g, dst.g),
+            b: mixed(src.b, dst.b),
+            a: sa + da - sa * da,
+        }
+    };
+
+    fn hard_light(s: f32, d: f32) -> f32 {
+        if s <= 0.5 {
+            d * 2.0 * s
+        } else {
+            let s = 2.0 * s - 1.0;
+            d + s - d * s
+        }
+    }
+
+    match mode {
+        CompositeMode::Clear => Color::default(),
+        CompositeMode::Source => src,
+        CompositeMode::Destination => dst,
+        CompositeMode::SourceOver => porter_duff(1.0, 1.0 - sa),
+        CompositeMode::DestinationOver => porter_duff(1.0 - da, 1.0),
+        CompositeMode::SourceIn => porter_duff(da, 0.0),
+        CompositeMode::DestinationIn => porter_duff(0.0, sa),
+        CompositeMode::SourceOut => porter_duff(1.0 - da, 0.0),
+        CompositeMode::DestinationOut => porter_duff(0.0, 1.0 - sa),
+        CompositeMode::SourceAtop => porter_duff(da, 1.0 - sa),
+        CompositeMode::DestinationAtop => porter_duff(1.0 - da, sa),
+        CompositeMode::Xor => porter_duff(1.0 - da, 1.0 - sa),
+        CompositeMode::Plus => src.zip(dst, |s, d| (s + d).min(1.0)),
+        CompositeMode::Multiply => blend(|s, d| s * d),
+        CompositeMode::Screen => blend(|s, d| s + d - s * d),
+        CompositeMode::Overlay => blend(|s, d| hard_light(d, s)),
+        CompositeMode::Darken => blend(f32::min),
+        CompositeMode::Lighten => blend(f32::max),
+        CompositeMode::ColorDodge => blend(|s, d| {
+            if d <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (d / (1.0 - s)).min(1.0)
+            }
+        }),
+        CompositeMode::ColorBurn => blend(|s, d| {
+            if d >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - d) / s).min(1.0)
+            }
+        }),
+        CompositeMode::HardLight => blend(hard_light),
+        CompositeMode::SoftLight => blend(|s, d| {
+            if s <= 0.5 {
+                d - (1.0 - 2.0 * s) * d * (1.0 - d)
+            } else {
+                let dd = if d <= 0.25 {
+                    ((16.0 * d - 12.0) * d + 4.0) * d
+                } else {
+                    d.sqrt()
+                };
+                d + (2.0 * s - 1.0) * (dd - d)
+            }
+        }),
+        CompositeMode::Difference => blend(|s, d| (s - d).abs()),
+        CompositeMode::Exclusion => blend(|s, d| s + d - 2.0 * s * d),
+        // Non-separable blend modes are rare in real fonts, treat them as normal
+        CompositeMode::Hue
+        | CompositeMode::Saturation
+        | CompositeMode::Color
+        | CompositeMode::Luminosity => porter_duff(1.0, 1.0 - sa),
+    }
+}
+
+fn apply(ts: &Transform, x: f32, y: f32) -> (f32, f32) {
+    (ts.a * x + ts.c * y + ts.e, ts.b * x + ts.d * y + ts.f)
+}
+
+fn invert(ts: &Transform) -> Option<Transform> {
+    let det = ts.a * ts.d - ts.b * ts.c;
+    if det.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let (a, b, c, d) = (ts.d / det, -ts.b / det, -ts.c / det, ts.a / det);
+    Some(Transform::new(
+        a,
+        b,
+        c,
+        d,
+        -(a * ts.e + c * ts.f),
+        -(b * ts.e + d * ts.f),
+    ))
+}
+
+/// Collect glyph outline as flattened contours in pixel space.
+struct PathBuilder {
+    transform: Transform,
+    contours: Vec<Vec<(f32, f32)>>,
+}
+
+impl PathBuilder {
+    fn new(transform: Transform) -> Self {
+        Self {
+            transform,
+            contours: vec![],
+        }
+    }
+
+    fn last(&self) -> (f32, f32) {
+        self.contours
+            .last()
+            .and_then(|c| c.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn push(&mut self, p: (f32, f32)) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push(p);
+        }
+    }
+
+    /// How many lines a curve should be flattened to, by length of its control polygon
+    fn segments(points: &[(f32, f32)]) -> usize {
+        let length: f32 = points
+            .windows(2)
+            .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+            .sum();
+        (length / 2.0).ceil().clamp(1.0, 32.0) as usize
+    }
+}
+
+impl OutlineBuilder for PathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(vec![apply(&self.transform, x, y)]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(apply(&self.transform, x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let p1 = apply(&self.transform, x1, y1);
+        let p2 = apply(&self.transform, x, y);
+        let n = Self::segments(&[p0, p1, p2]);
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let mt = 1.0 - t;
+            self.push((
+                mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+                mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+            ));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let p1 = apply(&self.transform, x1, y1);
+        let p2 = apply(&self.transform, x2, y2);
+        let p3 = apply(&self.transform, x, y);
+        let n = Self::segments(&[p0, p1, p2, p3]);
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let mt = 1.0 - t;
+            let (k0, k1, k2, k3) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            self.push((
+                k0 * p0.0 + k1 * p1.0 + k2 * p2.0 + k3 * p3.0,
+                k0 * p0.1 + k1 * p1.1 + k2 * p2.1 + k3 * p3.1,
+            ));
+        }
+    }
+
+    fn close(&mut self) {}
+}
+
+type Mask = Vec<f32>;
+
+struct Layer {
+    pixels: Vec<Color>,
+    mode: CompositeMode,
+}
+
+pub struct ColorPainter<'f, 'a> {
+    face: &'f Face<'a>,
+    width: usize,
+    height: usize,
+    transforms: Vec<Transform>,
+    outline: Option<Mask>,
+    clips: Vec<Mask>,
+    layers: Vec<Layer>,
+}
+
+impl<'f, 'a> ColorPainter<'f, 'a> {
+    /// Create a painter draws into `width` x `height` pixels, `base` transforms font units into
+    /// pixel coordinate.
+    pub fn new(face: &'f Face<'a>, width: usize, height: usize, base: Transform) -> Self {
+        Self {
+            face,
+            width,
+            height,
+            transforms: vec![base],
+            outline: None,
+            clips: vec![],
+            layers: vec![Layer {
+                pixels: vec![Color::default(); width * height],
+                mode: CompositeMode::SourceOver,
+            }],
+        }
+    }
+
+    pub fn paint_glyph(mut self, gid: GlyphId, foreground: RgbaColor) -> Option<Bitmap> {
+        let base = self.transforms[0];
+        self.face.paint_color_glyph(gid, 0, foreground, &mut self)?;
+
+        let pixels = &self.layers.first()?.pixels;
+        let mut colors = Grid::new(self.height, self.width);
+        for (i, color) in pixels.iter().enumerate() {
+            colors[(i / self.width, i % self.width)] = color.rgba();
+        }
+
+        let origin = (base.e.round() as i32, base.f.round() as i32);
+        Some(Bitmap::from_rgba(colors, origin))
+    }
+
+    fn transform(&self) -> Transform {
+        *self.transforms.last().expect("base transform never popped")
+    }
+
+    fn fill(&self, contours: &[Vec<(f32, f32)>]) -> Mask {
+        let mut mask = vec![0.0; self.width * self.height];
+        if self.width == 0 || self.height == 0 {
+            return mask;
+        }
+
+        // The coverage rasterizer do not clip horizontally, so we clamp points into canvas.
+        // Vertical edges at border keep the winding correct.
+        let max_x = (self.width - 1) as f32;
+        let p = |(x, y): (f32, f32)| point(x.clamp(0.0, max_x), y);
+
+        let mut coverage = Coverage::new(self.width, self.height);
+        for contour in contours {
+            let closing = contour.last().zip(contour.first());
+            for (from, to) in contour
+                .windows(2)
+                .map(|w| (w[0], w[1]))
+                .chain(closing.map(|(a, b)| (*a, *b)))
+            {
+                coverage.draw_line(p(from), p(to));
+            }
+        }
+
+        coverage.for_each_pixel(|i, alpha| mask[i] = alpha.min(1.0));
+        mask
+    }
+
+    fn clip(&self, i: usize) -> f32 {
+        self.clips.last().map(|mask| mask[i]).unwrap_or(1.0)
+    }
+
+    fn push_mask(&mut self, mask: Mask) {
+        let combined = match self.clips.last() {
+            Some(last) => last.iter().zip(mask).map(|(a, b)| a * b).collect(),
+            None => mask,
+        };
+        self.clips.push(combined);
+    }
+
+    fn paint_with<F>(&mut self, color_at: F)
+    where
+        F: Fn(f32, f32) -> Option<Color>,
+    {
+        // Map pixel center back into the coordinate space the paint is defined in
+        let Some(inverse) = invert(&self.transform()) else {
+            return;
+        };
+
+        let mut pixels = std::mem::take(
+            &mut self
+                .layers
+                .last_mut()
+                .expect("root layer never popped")
+                .pixels,
+        );
+
+        for (i, dst) in pixels.iter_mut().enumerate() {
+            let mut coverage = self.clip(i);
+            if let Some(outline) = &self.outline {
+                coverage *= outline[i];
+            }
+            if coverage <= 0.0 {
+                continue;
+            }
+
+            let x = (i % self.width) as f32 + 0.5;
+            let y = (i / self.width) as f32 + 0.5;
+            let (x, y) = apply(&inverse, x, y);
+
+            if let Some(src) = color_at(x, y) {
+                *dst = composite(CompositeMode::SourceOver, src.scale(coverage), *dst);
+            }
+        }
+
+        self.layers
+            .last_mut()
+            .expect("root layer never popped")
+            .pixels = pixels;
+    }
+}
+
+/// Sorted color stops of a gradient
+struct ColorLine {
+    stops: Vec<(f32, Color)>,
+    extend: GradientExtend,
+}
+
+impl ColorLine {
+    fn new(stops: impl Iterator<Item = ColorStop>, extend: GradientExtend) -> Self {
+        let mut stops: Vec<_> = stops
+            .map(|s| (s.stop_offset, Color::new(s.color)))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops, extend }
+    }
+
+    fn color(&self, t: f32) -> Option<Color> {
+        let first = self.stops.first()?;
+        let last = self.stops.last()?;
+
+        let range = last.0 - first.0;
+        let t = if range > 0.0 {
+            let u = (t - first.0) / range;
+            let u = match self.extend {
+                GradientExtend::Pad => u.clamp(0.0, 1.0),
+                GradientExtend::Repeat => u - u.floor(),
+                GradientExtend::Reflect => {
+                    let u = u.rem_euclid(2.0);
+                    if u > 1.0 { 2.0 - u } else { u }
+                }
+            };
+            first.0 + u * range
+        } else {
+            first.0
+        };
+
+        if t <= first.0 {
+            return Some(first.1);
+        }
+
+        self.stops
+            .windows(2)
+            .find_map(|w| {
+                let ((o0, c0), (o1, c1)) = (w[0], w[1]);
+                (t <= o1).then(|| {
+                    if o1 > o0 {
+                        c0.lerp(c1, (t - o0) / (o1 - o0))
+                    } else {
+                        c1
+                    }
+                })
+            })
+            .or(Some(last.1))
+    }
+}
+
+impl<'a> Painter<'a> for ColorPainter<'_, 'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        let mut builder = PathBuilder::new(self.transform());
+        self.outline = self
+            .face
+            .outline_glyph(glyph_id, &mut builder)
+            .map(|_| self.fill(&builder.contours));
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        // Variable color stops need normalized coords live as long as font data, which we can't
+        // provide, so default values are used.
+        match paint {
+            Paint::Solid(color) => {
+                let color = Color::new(color);
+                self.paint_with(|_, _| Some(color));
+            }
+            Paint::LinearGradient(g) => {
+                let line = ColorLine::new(g.stops(0, &[]), g.extend);
+
+                // Gradient vector is p0p1 projected onto the normal of p0p2
+                let (nx, ny) = (g.y2 - g.y0, g.x0 - g.x2);
+                let (dx, dy) = (g.x1 - g.x0, g.y1 - g.y0);
+                let n2 = nx * nx + ny * ny;
+                let (vx, vy) = if n2 > 0.0 {
+                    let k = (dx * nx + dy * ny) / n2;
+                    (nx * k, ny * k)
+                } else {
+                    (dx, dy)
+                };
+                let v2 = vx * vx + vy * vy;
+
+                self.paint_with(|x, y| {
+                    let t = if v2 > 0.0 {
+                        ((x - g.x0) * vx + (y - g.y0) * vy) / v2
+                    } else {
+                        0.0
+                    };
+                    line.color(t)
+                });
+            }
+            Paint::RadialGradient(g) => {
+                let line = ColorLine::new(g.stops(0, &[]), g.extend);
+
+                // Find max t makes point on circle (c0 + t * (c1 - c0), r0 + t * (r1 - r0))
+                let (cdx, cdy, dr) = (g.x1 - g.x0, g.y1 - g.y0, g.r1 - g.r0);
+                let a = cdx * cdx + cdy * cdy - dr * dr;
+
+                self.paint_with(|x, y| {
+                    let (px, py) = (x - g.x0, y - g.y0);
+                    let b = px * cdx + py * cdy + g.r0 * dr;
+                    let c = px * px + py * py - g.r0 * g.r0;
+
+                    let t = if a.abs() < f32::EPSILON {
+                        if b.abs() < f32::EPSILON {
+                            return None;
+                        }
+                        c / (2.0 * b)
+                    } else {
+                        let discriminant = b * b - a * c;
+                        if discriminant < 0.0 {
+                            return None;
+                        }
+                        let sqrt = discriminant.sqrt();
+                        let (t1, t2) = ((b + sqrt) / a, (b - sqrt) / a);
+                        let (big, small) = if t1 > t2 { (t1, t2) } else { (t2, t1) };
+                        if g.r0 + big * dr >= 0.0 {
+                            big
+                        } else if g.r0 + small * dr >= 0.0 {
+                            small
+                        } else {
+                            return None;
+                        }
+                    };
+
+                    if g.r0 + t * dr < 0.0 {
+                        return None;
+                    }
+
+                    line.color(t)
+                });
+            }
+            Paint::SweepGradient(g) => {
+                let line = ColorLine::new(g.stops(0, &[]), g.extend);
+
+                // Angles are stored as 180 degrees per 1.0
+                let start = g.start_angle * 180.0;
+                let end = g.end_angle * 180.0;
+
+                self.paint_with(|x, y| {
+                    let angle = (y - g.center_y)
+                        .atan2(x - g.center_x)
+                        .to_degrees()
+                        .rem_euclid(360.0);
+                    let t = if (end - start).abs() > f32::EPSILON {
+                        (angle - start) / (end - start)
+                    } else {
+                        0.0
+                    };
+                    line.color(t)
+                });
+            }
+        }
+    }
+
+    fn push_clip(&mut self) {
+        // After this, the outline is represented by clip, do not mask paint by it again
+        let mask = self
+            .outline
+            .take()
+            .unwrap_or_else(|| vec![0.0; self.width * self.height]);
+        self.push_mask(mask);
+    }
+
+    fn push_clip_box(&mut self, clipbox: ClipBox) {
+        let ts = self.transform();
+        let rect = vec![
+            apply(&ts, clipbox.x_min, clipbox.y_min),
+            apply(&ts, clipbox.x_max, clipbox.y_min),
+            apply(&ts, clipbox.x_max, clipbox.y_max),
+            apply(&ts, clipbox.x_min, clipbox.y_max),
+        ];
+        let mask = self.fill(&[rect]);
+        self.push_mask(mask);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn push_layer(&mut self, mode: CompositeMode) {
+        self.layers.push(Layer {
+            pixels: vec![Color::default(); self.width * self.height],
+            mode,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        if self.layers.len() <= 1 {
+            return;
+        }
+
+        let src = self.layers.pop().expect("checked length before");
+        let dst = self.layers.last_mut().expect("checked length before");
+        for (d, s) in dst.pixels.iter_mut().zip(src.pixels) {
+            *d = composite(src.mode, s, *d);
+        }
+    }
+
+    fn push_transform(&mut self, transform: Transform) {
+        let combined = Transform::combine(self.transform(), transform);
+        self.transforms.push(combined);
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color::new(RgbaColor::new(r, g, b, a))
+    }
+
+    fn gray_line(extend: GradientExtend) -> ColorLine {
+        let stops = [(0.0, 0), (1.0, 200)].map(|(stop_offset, v)| ColorStop {
+            stop_offset,
+            color: RgbaColor::new(v, v, v, 255),
+        });
+        // Stops are sorted by offset
+        ColorLine::new(stops.into_iter().rev(), extend)
+    }
+
+    #[test]
+    fn color_line_extend() {
+        let at = |line: &ColorLine, t: f32| line.color(t).map(|c| c.rgba()[0]);
+
+        let pad = gray_line(GradientExtend::Pad);
+        assert_eq!(at(&pad, 0.5), Some(100));
+        assert_eq!(at(&pad, -0.5), Some(0));
+        assert_eq!(at(&pad, 1.5), Some(200));
+
+        let repeat = gray_line(GradientExtend::Repeat);
+        assert_eq!(at(&repeat, 1.25), Some(50));
+        assert_eq!(at(&repeat, -0.25), Some(150));
+
+        let reflect = gray_line(GradientExtend::Reflect);
+        assert_eq!(at(&reflect, 1.25), Some(150));
+        assert_eq!(at(&reflect, -0.25), Some(50));
+        assert_eq!(at(&reflect, 2.25), Some(50));
+
+        let empty = ColorLine::new(std::iter::empty(), GradientExtend::Pad);
+        assert!(empty.color(0.5).is_none());
+    }
+
+    #[test]
+    fn composite_operators() {
+        let red = color(255, 0, 0, 128);
+        let blue = color(0, 0, 255, 255);
+        let mix = |mode| composite(mode, red, blue).rgba();
+
+        assert_eq!(mix(CompositeMode::Clear), [0; 4]);
+        assert_eq!(mix(CompositeMode::Source), [255, 0, 0, 128]);
+        assert_eq!(mix(CompositeMode::Destination), [0, 0, 255, 255]);
+        assert_eq!(mix(CompositeMode::SourceOver), [128, 0, 127, 255]);
+        assert_eq!(mix(CompositeMode::DestinationOver), [0, 0, 255, 255]);
+        assert_eq!(mix(CompositeMode::SourceIn), [255, 0, 0, 128]);
+        assert_eq!(mix(CompositeMode::DestinationIn), [0, 0, 255, 128]);
+        assert_eq!(mix(CompositeMode::SourceOut), [0; 4]);
+        assert_eq!(mix(CompositeMode::DestinationOut), [0, 0, 255, 127]);
+        assert_eq!(mix(CompositeMode::SourceAtop), [128, 0, 127, 255]);
+        assert_eq!(mix(CompositeMode::Xor), [0, 0, 255, 127]);
+        assert_eq!(mix(CompositeMode::Plus), [128, 0, 255, 255]);
+        assert_eq!(mix(CompositeMode::Multiply), [0, 0, 127, 255]);
+        assert_eq!(mix(CompositeMode::Screen), [128, 0, 255, 255]);
+        assert_eq!(mix(CompositeMode::Lighten), [128, 0, 255, 255]);
+        assert_eq!(mix(CompositeMode::Darken), [0, 0, 127, 255]);
+        assert_eq!(mix(CompositeMode::Difference), [128, 0, 255, 255]);
+    }
+
+    /// Font with a 100 units square as glyph 1, and color glyph 2 and 3 which paint it by
+    /// palette entry 0 and by foreground (`0xFFFF`).
+    fn font() -> Vec<u8> {
+        let be =
+            |values: &[u16]| -> Vec<u8> { values.iter().flat_map(|v| v.to_be_bytes()).collect() };
+
+        let mut head = vec![0; 54];
+        head[18..20].copy_from_slice(&100u16.to_be_bytes());
+        let mut hhea = vec![0; 36];
+        hhea[4..6].copy_from_slice(&100u16.to_be_bytes());
+        let maxp = be(&[0, 0x5000, 4]);
+
+        // One contour of four on-curve points, coordinates are i16 deltas
+        let glyf = be(&[
+            1, 0, 0, 100, 100, 3, 0, 0x0101, 0x0101, 0, 0, 100, 0, 0, 100, 0, 0xFF9C,
+        ]);
+        let loca = be(&[0, 0, 17, 17, 17]);
+
+        let colr = be(&[0, 2, 0, 14, 0, 26, 2, 2, 0, 1, 3, 1, 1, 1, 0, 1, 0xFFFF]);
+        let mut cpal = be(&[0, 1, 1, 1, 0, 14, 0]);
+        cpal.extend([0x40, 0x80, 0xC0, 0xFF]);
+
+        let tables = [
+            (b"COLR", colr),
+            (b"CPAL", cpal),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        let mut data = be(&[1, 0, tables.len() as u16, 0, 0, 0]);
+        let mut body = vec![];
+        for (tag, table) in &tables {
+            let offset = 12 + tables.len() * 16 + body.len();
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            body.extend(table);
+            body.resize(body.len().next_multiple_of(4), 0);
+        }
+        data.extend(body);
+        data
+    }
+
+    #[test]
+    fn palette_and_foreground() {
+        let data = font();
+        let face = Face::parse(&data, 0).unwrap();
+        let foreground = RgbaColor::new(10, 20, 30, 255);
+        // Square covers pixel 1 ~ 5 of a 6x6 canvas, bitmap is cropped to it
+        let base = Transform::new(0.04, 0.0, 0.0, -0.04, 1.0, 5.0);
+        let paint = |gid| {
+            let bitmap = ColorPainter::new(&face, 6, 6, base)
+                .paint_glyph(GlyphId(gid), foreground)
+                .unwrap();
+            let m = bitmap.metrics();
+            assert_eq!((m.width, m.height), (4, 4));
+            let mut colors: Vec<_> = bitmap.colors().unwrap().iter().copied().collect();
+            colors.dedup();
+            colors
+        };
+
+        assert_eq!(paint(2), [[0xC0, 0x80, 0x40, 0xFF]]);
+        assert_eq!(paint(3), [[10, 20, 30, 255]]);
+        assert!(
+            ColorPainter::new(&face, 6, 6, base)
+                .paint_glyph(GlyphId(1), foreground)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,79 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GuideKind {
+    Ascender,
+    CapHeight,
+    XHeight,
+    Baseline,
+    Descender,
+    Origin,
+    Advance,
+}
+
+impl GuideKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ascender => "ascender",
+            Self::CapHeight => "cap height",
+            Self::XHeight => "x-height",
+            Self::Baseline => "baseline",
+            Self::Descender => "descender",
+            Self::Origin => "origin",
+            Self::Advance => "advance",
+        }
+    }
+
+    /// Vertical guides mark a column, others mark a row.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::Origin | Self::Advance)
+    }
+}
+
+/// A guide line in bitmap, `position` is a row or a column index depends on its kind.
+#[derive(Debug, Copy, Clone)]
+pub struct Guide {
+    pub kind: GuideKind,
+    pub position: usize,
+}
+
+/// Font metrics in pixels, vertical values are distances above baseline.
+pub struct EmMetrics {
+    pub ascender: i32,
+    pub cap_height: Option<i32>,
+    pub x_height: Option<i32>,
+    pub descender: i32,
+    pub advance: i32,
+}
+
+impl EmMetrics {
+    /// Horizontal guide lines as (kind, distance above baseline), baseline comes last to be drawn
+    /// on top.
+    pub fn horizontal_lines(&self) -> impl Iterator<Item = (GuideKind, i32)> {
+        [
+            Some((GuideKind::Ascender, self.ascender)),
+            self.cap_height.map(|v| (GuideKind::CapHeight, v)),
+            self.x_height.map(|v| (GuideKind::XHeight, v)),
+            Some((GuideKind::Descender, self.descender)),
+            Some((GuideKind::Baseline, 0)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
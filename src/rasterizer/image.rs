@@ -0,0 +1,279 @@
+// FontFor: find fonts which can show a specified character
+// Copyright (C) 2019 - 2025 7sDream <i@7sdre.am> and contributors
+//
+// This file is part of FontFor.
+//
+// FontFor is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use grid::Grid;
+use png::{ColorType, Decoder, Transformations};
+use ttf_parser::{Face, GlyphId, RasterGlyphImage, RasterImageFormat, Tag};
+
+use super::bitmap::Rgba;
+
+const EBLC_TAG: Tag = Tag::from_bytes(b"EBLC");
+const CBLC_TAG: Tag = Tag::from_bytes(b"CBLC");
+
+/// Size of a `BitmapSize` record in `EBLC`/`CBLC` table
+const BITMAP_SIZE_RECORD_LEN: usize = 48;
+
+/// Decoded embedded glyph image
+pub struct Image {
+    pub pixels: Grid<Rgba>,
+    /// `false` for mono and gray bitmaps, whose alpha channel is the only meaningful part
+    pub colored: bool,
+}
+
+fn decode_png(data: &[u8]) -> Option<Grid<Rgba>> {
+    let mut decoder = Decoder::new(data);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).ok()?;
+
+    let (width, height) = (info.width as usize, info.height as usize);
+    let channels = info.color_type.samples();
+
+    let mut image = Grid::new(height, width);
+    for row in 0..height {
+        let line = &buffer[row * info.line_size..];
+        for col in 0..width {
+            let p = &line[col * channels..(col + 1) * channels];
+            image[(row, col)] = match info.color_type {
+                ColorType::Rgba => [p[0], p[1], p[2], p[3]],
+                ColorType::Rgb => [p[0], p[1], p[2], 255],
+                ColorType::GrayscaleAlpha => [p[0], p[0], p[0], p[1]],
+                ColorType::Grayscale => [p[0], p[0], p[0], 255],
+                // normalize_to_color8 expands palette into RGB(A)
+                ColorType::Indexed => return None,
+            };
+        }
+    }
+
+    Some(image)
+}
+
+fn decode_bgra(image: &RasterGlyphImage<'_>) -> Option<Grid<Rgba>> {
+    let (width, height) = (usize::from(image.width), usize::from(image.height));
+    if image.data.len() < width * height * 4 {
+        return None;
+    }
+
+    let mut result = Grid::new(height, width);
+    for (i, p) in image.data.chunks_exact(4).take(width * height).enumerate() {
+        let [b, g, r, a] = [p[0], p[1], p[2], p[3]];
+        let unpremultiply = |c: u8| {
+            if a == 0 {
+                0
+            } else {
+                (u16::from(c) * 255 / u16::from(a)).min(255) as u8
+            }
+        };
+        result[(i / width, i % width)] = [unpremultiply(r), unpremultiply(g), unpremultiply(b), a];
+    }
+
+    Some(result)
+}
+
+/// Decode mono or gray bitmap with `bits` bits per pixel, ink intensity goes to alpha channel.
+///
+/// Rows of not packed bitmap are padded to byte boundary.
+fn decode_gray(image: &RasterGlyphImage<'_>, bits: usize, packed: bool) -> Option<Grid<Rgba>> {
+    let (width, height) = (usize::from(image.width), usize::from(image.height));
+    let stride = if packed {
+        width * bits
+    } else {
+        (width * bits).div_ceil(8) * 8
+    };
+    let max = (1u16 << bits) - 1;
+
+    let mut result = Grid::new(height, width);
+    for row in 0..height {
+        for col in 0..width {
+            let bit = row * stride + col * bits;
+            let byte = *image.data.get(bit / 8)?;
+            let value = u16::from(byte >> (8 - bits - bit % 8)) & max;
+            result[(row, col)] = [0, 0, 0, (value * 255 / max) as u8];
+        }
+    }
+
+    Some(result)
+}
+
+/// Decode embedded glyph image into RGBA pixels
+pub fn decode(image: &RasterGlyphImage<'_>) -> Option<Image> {
+    let gray = |bits, packed| {
+        decode_gray(image, bits, packed).map(|pixels| Image {
+            pixels,
+            colored: false,
+        })
+    };
+    let color = |pixels: Option<Grid<Rgba>>| {
+        pixels.map(|pixels| Image {
+            pixels,
+            colored: true,
+        })
+    };
+
+    match image.format {
+        RasterImageFormat::PNG => color(decode_png(image.data)),
+        RasterImageFormat::BitmapPremulBgra32 => color(decode_bgra(image)),
+        RasterImageFormat::BitmapMono => gray(1, false),
+        RasterImageFormat::BitmapMonoPacked => gray(1, true),
+        RasterImageFormat::BitmapGray2 => gray(2, false),
+        RasterImageFormat::BitmapGray2Packed => gray(2, true),
+        RasterImageFormat::BitmapGray4 => gray(4, false),
+        RasterImageFormat::BitmapGray4Packed => gray(4, true),
+        RasterImageFormat::BitmapGray8 => gray(8, false),
+    }
+}
+
+fn parse_strikes(data: &[u8], gid: GlyphId) -> Option<Vec<u16>> {
+    let read_u16 = |offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(
+            data.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+
+    // majorVersion, minorVersion, numSizes, then BitmapSize records
+    let count = u32::from_be_bytes(data.get(4..8)?.try_into().ok()?) as usize;
+
+    let mut strikes = vec![];
+    for i in 0..count {
+        let record = 8 + i * BITMAP_SIZE_RECORD_LEN;
+        let start = read_u16(record + 40)?;
+        let end = read_u16(record + 42)?;
+        let ppem_y = *data.get(record + 45)?;
+        if (start..=end).contains(&gid.0) {
+            strikes.push(u16::from(ppem_y));
+        }
+    }
+
+    Some(strikes)
+}
+
+/// Sizes (in ppem) of all `EBLC`/`CBLC` bitmap strikes which may contain the glyph.
+pub fn strikes(face: &Face<'_>, gid: GlyphId) -> Vec<u16> {
+    [EBLC_TAG, CBLC_TAG]
+        .into_iter()
+        .filter_map(|tag| face.raw_face().table(tag))
+        .find_map(|data| parse_strikes(data, gid))
+        .unwrap_or_default()
+}
+
+/// The strike whose size is closest to `ppem`, prefer the bigger one if tie.
+pub fn closest_strike(strikes: &[u16], ppem: f32) -> Option<u16> {
+    strikes.iter().copied().min_by(|a, b| {
+        let da = (f32::from(*a) - ppem).abs();
+        let db = (f32::from(*b) - ppem).abs();
+        da.total_cmp(&db).then(b.cmp(a))
+    })
+}
+
+/// Resize image by averaging all source pixels covered by each target pixel.
+pub fn resize(src: &Grid<Rgba>, width: usize, height: usize) -> Grid<Rgba> {
+    let mut result = Grid::new(height, width);
+    if src.rows() == 0 || src.cols() == 0 {
+        return result;
+    }
+
+    let sx = src.cols() as f32 / width as f32;
+    let sy = src.rows() as f32 / height as f32;
+
+    // Take n * n samples in each target pixel, at least 1, at most 8
+    let nx = sx.ceil().clamp(1.0, 8.0) as usize;
+    let ny = sy.ceil().clamp(1.0, 8.0) as usize;
+
+    for row in 0..height {
+        for col in 0..width {
+            // Average in premultiplied space to avoid dark fringe
+            let mut sum = [0.0f32; 4];
+            for j in 0..ny {
+                for i in 0..nx {
+                    let x = ((col as f32 + (i as f32 + 0.5) / nx as f32) * sx) as usize;
+                    let y = ((row as f32 + (j as f32 + 0.5) / ny as f32) * sy) as usize;
+                    let p = src[(y.min(src.rows() - 1), x.min(src.cols() - 1))];
+                    let a = f32::from(p[3]);
+                    sum[0] += f32::from(p[0]) * a;
+                    sum[1] += f32::from(p[1]) * a;
+                    sum[2] += f32::from(p[2]) * a;
+                    sum[3] += a;
+                }
+            }
+
+            result[(row, col)] = if sum[3] > 0.0 {
+                let count = (nx * ny) as f32;
+                [
+                    (sum[0] / sum[3]).round() as u8,
+                    (sum[1] / sum[3]).round() as u8,
+                    (sum[2] / sum[3]).round() as u8,
+                    (sum[3] / count).round() as u8,
+                ]
+            } else {
+                [0; 4]
+            };
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `EBLC`/`CBLC` table with BitmapSize records of (startGlyphIndex, endGlyphIndex, ppemX,
+    /// ppemY), other fields are filled with 0xFF so reading a wrong offset is noticed.
+    fn table(records: &[(u16, u16, u8, u8)]) -> Vec<u8> {
+        let mut data = vec![0, 2, 0, 0];
+        data.extend((records.len() as u32).to_be_bytes());
+        for &(start, end, ppem_x, ppem_y) in records {
+            let mut record = [0xFF; BITMAP_SIZE_RECORD_LEN];
+            record[40..42].copy_from_slice(&start.to_be_bytes());
+            record[42..44].copy_from_slice(&end.to_be_bytes());
+            record[44] = ppem_x;
+            record[45] = ppem_y;
+            data.extend(record);
+        }
+        data
+    }
+
+    #[test]
+    fn strikes_contain_glyph() {
+        let data = table(&[(1, 100, 10, 11), (50, 300, 20, 21), (200, 400, 30, 31)]);
+        assert_eq!(parse_strikes(&data, GlyphId(0)), Some(vec![]));
+        assert_eq!(parse_strikes(&data, GlyphId(1)), Some(vec![11]));
+        assert_eq!(parse_strikes(&data, GlyphId(100)), Some(vec![11, 21]));
+        assert_eq!(parse_strikes(&data, GlyphId(250)), Some(vec![21, 31]));
+        assert_eq!(parse_strikes(&data, GlyphId(400)), Some(vec![31]));
+    }
+
+    #[test]
+    fn truncated_strikes() {
+        let data = table(&[(1, 100, 10, 11)]);
+        assert_eq!(parse_strikes(&data[..4], GlyphId(1)), None);
+        assert_eq!(parse_strikes(&data[..8 + 45], GlyphId(1)), None);
+        assert_eq!(parse_strikes(&data[..8 + 46], GlyphId(1)), Some(vec![11]));
+    }
+
+    #[test]
+    fn closest() {
+        assert_eq!(closest_strike(&[], 12.0), None);
+        assert_eq!(closest_strike(&[16, 32, 64], 20.0), Some(16));
+        assert_eq!(closest_strike(&[16, 32, 64], 100.0), Some(64));
+        // Prefer the bigger one if tie
+        assert_eq!(closest_strike(&[32, 16], 24.0), Some(32));
+    }
+}
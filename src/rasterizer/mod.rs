@@ -17,42 +17,263 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 mod bitmap;
+mod color;
+mod guide;
+mod image;
 
-use ab_glyph::{Font, FontRef, GlyphId, InvalidFont, PxScale};
+use ab_glyph::{Font, FontRef, GlyphId, InvalidFont, PxScale, VariableFont};
+use thiserror::Error;
+use ttf_parser::{Face, RgbaColor, Transform};
 
-pub use self::bitmap::Bitmap;
+pub use self::{
+    bitmap::{Bitmap, Overlap, Rgba},
+    guide::{Guide, GuideKind},
+};
+use self::{color::ColorPainter, guide::EmMetrics};
+use crate::loader::{self, AxisValue, FaceInfo};
+
+/// Max width or height of color glyph canvas, avoid huge allocation for broken fonts
+const MAX_CANVAS_SIZE: f32 = 4096.0;
+
+/// How large glyph is rasterized.
+#[derive(Debug, Copy, Clone)]
+pub enum Size {
+    /// Pixels from ascender to descender, the shared ones if set
+    Height(u32),
+    /// Pixels per em, which keeps the same scale for glyphs of different faces
+    Em(u32),
+}
+
+/// How glyphs are rasterized, the same for all glyphs of a face.
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    pub size: Size,
+    /// Horizontal scale, to fit non-square terminal cells
+    pub hscale: f32,
+    /// Color used by color glyph layers which ask for "current text color"
+    pub foreground: Rgba,
+    /// Keep the whole em box around glyph instead of cropping it tightly, see [`Bitmap::guides`]
+    pub em_box: bool,
+    /// Shared `ascender` and `descender` in ems used instead of the face's own ones, for pixel
+    /// height and em box, so glyphs of different faces have the same size and baseline
+    pub shared_em: Option<(f32, f32)>,
+}
+
+impl Options {
+    pub const fn new(size: Size) -> Self {
+        Self {
+            size,
+            hscale: 1.0,
+            foreground: [0, 0, 0, 255],
+            em_box: false,
+            shared_em: None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Error)]
+#[error("{}", self.message())]
+pub enum Error {
+    InvalidFont,
+    ReadFontFailed,
+    MissingGlyph,
+}
+
+impl Error {
+    /// Display text of the error, for callers which keep errors as static strings.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::InvalidFont => "Can't parse font file",
+            Self::ReadFontFailed => "Can't read font file",
+            Self::MissingGlyph => "Can't get target glyph from this font",
+        }
+    }
+}
+
+/// Load the face once and call `f` with a rasterizer of it at `coordinates`.
+pub fn with_face<T>(
+    face: &FaceInfo, coordinates: &[AxisValue], options: Options,
+    f: impl FnOnce(&Rasterizer<'_>) -> T,
+) -> Result<T, Error> {
+    loader::database()
+        .with_face_data(face.id, |data, index| {
+            let mut r = Rasterizer::new(data, index, options).map_err(|_| Error::InvalidFont)?;
+            r.set_variations(coordinates);
+            Ok(f(&r))
+        })
+        .unwrap_or(Err(Error::ReadFontFailed))
+}
+
+/// Rasterize the glyph of the face at `coordinates`.
+pub fn rasterize_face(
+    face: &FaceInfo, coordinates: &[AxisValue], options: Options,
+) -> Result<Bitmap, Error> {
+    with_face(face, coordinates, options, |r| r.rasterize(face.gid))?.ok_or(Error::MissingGlyph)
+}
 
 pub struct Rasterizer<'a> {
     face: FontRef<'a>,
-    height: u32,
-    hscale: f32,
+    parsed: Face<'a>,
+    options: Options,
 }
 
 impl<'a> Rasterizer<'a> {
-    pub fn new(data: &'a [u8], index: u32) -> Result<Self, InvalidFont> {
+    pub fn new(data: &'a [u8], index: u32, options: Options) -> Result<Self, InvalidFont> {
         let face = FontRef::try_from_slice_and_index(data, index)?;
+        let parsed = Face::parse(data, index).map_err(|_| InvalidFont)?;
         Ok(Self {
             face,
-            height: 0,
-            hscale: 1.0,
+            parsed,
+            options,
         })
     }
 
-    pub fn set_pixel_height(&mut self, height: u32) {
-        self.height = height;
+    fn set_variations(&mut self, coordinates: &[AxisValue]) {
+        for c in coordinates {
+            if !self.face.set_variation(&c.tag.to_bytes(), c.value) {
+                log::warn!("Font do not have variation axis {}", c.tag);
+            }
+            self.parsed.set_variation(c.tag, c.value);
+        }
+    }
+
+    /// Pixels per font unit in vertical direction.
+    fn scale(&self) -> f32 {
+        let (pixels, units) = match self.options.size {
+            Size::Height(height) => (height, self.box_height()),
+            Size::Em(ppem) => (ppem, f32::from(self.parsed.units_per_em())),
+        };
+        if units > 0.0 {
+            pixels as f32 / units
+        } else {
+            0.0
+        }
+    }
+
+    /// Ascender to descender in font units, the shared ones if set.
+    fn box_height(&self) -> f32 {
+        match self.options.shared_em {
+            Some((ascender, descender)) => {
+                (ascender - descender) * f32::from(self.parsed.units_per_em())
+            }
+            None => self.line_height(),
+        }
     }
 
-    pub fn set_hscale(&mut self, scale: f32) {
-        self.hscale = scale
+    /// Ascender to descender in font units, which ab_glyph scales to [`PxScale`] height.
+    fn line_height(&self) -> f32 {
+        f32::from(self.parsed.ascender()) - f32::from(self.parsed.descender())
     }
 
-    pub fn rasterize(self, gid: u16) -> Option<Bitmap> {
+    fn rasterize_color(&self, gid: u16) -> Option<Bitmap> {
+        let sy = self.scale();
+        let sx = sy * self.options.hscale;
+
+        let bbox = self.parsed.global_bounding_box();
+        let (x_min, y_max) = (f32::from(bbox.x_min), f32::from(bbox.y_max));
+
+        // 1px padding for anti-aliasing
+        let width = ((f32::from(bbox.width()) * sx).ceil() + 2.0).min(MAX_CANVAS_SIZE);
+        let height = ((f32::from(bbox.height()) * sy).ceil() + 2.0).min(MAX_CANVAS_SIZE);
+
+        // Font units to pixels, y axis flipped
+        let base = Transform::new(sx, 0.0, 0.0, -sy, 1.0 - x_min * sx, 1.0 + y_max * sy);
+
+        let [r, g, b, a] = self.options.foreground;
+        ColorPainter::new(&self.parsed, width as usize, height as usize, base)
+            .paint_glyph(ttf_parser::GlyphId(gid), RgbaColor::new(r, g, b, a))
+    }
+
+    fn rasterize_image(&self, gid: u16) -> Option<Bitmap> {
+        let glyph_id = ttf_parser::GlyphId(gid);
+        let ppem = self.scale() * f32::from(self.parsed.units_per_em());
+
+        // ttf-parser picks the smallest strike not less than requested size,
+        // so ask for the closest one exactly.
+        let strikes = image::strikes(&self.parsed, glyph_id);
+        let request = image::closest_strike(&strikes, ppem).unwrap_or(ppem.round() as u16);
+        let image = self.parsed.glyph_raster_image(glyph_id, request)?;
+
+        let decoded = image::decode(&image)?;
+        let pixels = &decoded.pixels;
+
+        let k = ppem / f32::from(image.pixels_per_em.max(1));
+        let width = ((pixels.cols() as f32 * k * self.options.hscale).round() as usize).max(1);
+        let height = ((pixels.rows() as f32 * k).round() as usize).max(1);
+
+        // Image offset is its left bottom corner relative to origin, y axis goes up
+        let origin = (
+            (-f32::from(image.x) * k * self.options.hscale).round() as i32,
+            ((f32::from(image.y) + pixels.rows() as f32) * k).round() as i32,
+        );
+
+        let resized = image::resize(pixels, width, height);
+        let bitmap = if decoded.colored {
+            Bitmap::from_rgba(resized, origin)
+        } else {
+            Bitmap::from_alpha(resized, origin)
+        };
+
+        Some(bitmap.with_strike(image.pixels_per_em))
+    }
+
+    /// Font metrics from `hhea`/`OS/2` table in pixels.
+    fn em_metrics(&self, gid: u16) -> EmMetrics {
+        let sy = self.scale();
+        let sx = sy * self.options.hscale;
+        let y = |v: i16| (f32::from(v) * sy).round() as i32;
+
+        let advance = self
+            .parsed
+            .glyph_hor_advance(ttf_parser::GlyphId(gid))
+            .unwrap_or_default();
+
+        let (ascender, descender) = match self.options.shared_em {
+            Some((ascender, descender)) => {
+                let ppem = sy * f32::from(self.parsed.units_per_em());
+                let em = |v: f32| (v * ppem).round() as i32;
+                (em(ascender), em(descender))
+            }
+            None => (y(self.parsed.ascender()), y(self.parsed.descender())),
+        };
+
+        EmMetrics {
+            ascender,
+            cap_height: self.parsed.capital_height().filter(|&v| v > 0).map(y),
+            x_height: self.parsed.x_height().filter(|&v| v > 0).map(y),
+            descender,
+            advance: (f32::from(advance) * sx).round() as i32,
+        }
+    }
+
+    pub fn rasterize(&self, gid: u16) -> Option<Bitmap> {
+        let bitmap = self.rasterize_glyph(gid)?;
+        Some(if self.options.em_box {
+            bitmap.into_em_box(&self.em_metrics(gid))
+        } else {
+            bitmap
+        })
+    }
+
+    fn rasterize_glyph(&self, gid: u16) -> Option<Bitmap> {
+        if self.parsed.is_color_glyph(ttf_parser::GlyphId(gid))
+            && let Some(bitmap) = self.rasterize_color(gid)
+        {
+            return Some(bitmap);
+        }
+
         let glyph_id = GlyphId(gid);
+        // ab_glyph scales ascender to descender of the face to PxScale height
+        let height = self.scale() * self.line_height();
         let glyph = glyph_id.with_scale(PxScale {
-            x: self.height as f32 * self.hscale,
-            y: self.height as f32,
+            x: height * self.options.hscale,
+            y: height,
         });
-        let curve = self.face.outline_glyph(glyph)?;
-        Some(Bitmap::new(&curve))
+        if let Some(curve) = self.face.outline_glyph(glyph) {
+            return Some(Bitmap::new(&curve));
+        }
+
+        // SVG glyphs are not supported, they need a full SVG renderer
+        self.rasterize_image(gid)
     }
 }